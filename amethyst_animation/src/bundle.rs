@@ -1,11 +1,16 @@
+use std::marker::PhantomData;
+
 use amethyst_assets::AssetStorage;
-use amethyst_core::{ECSBundle, Result};
-use specs::{DispatcherBuilder, World};
+use amethyst_core::{Clock, ECSBundle, Result, SystemsBuilder};
+use shrev::EventChannel;
+use specs::{DispatcherBuilder, Fetch, SystemData, World};
 
+use path::{FollowPath, FollowPathSystem, Path};
 use resources::{Animation, AnimationControl, AnimationHierarchy, AnimationSet, Sampler,
                 SamplerControlSet};
-use systems::{AnimationControlSystem, AnimationProcessor, SamplerInterpolationSystem,
-              SamplerProcessor};
+use tween::{Tween, TweenCompleted, TweenSystem, Tweenable};
+use systems::{AnimationControlSystem, AnimationProcessor, PathProcessor,
+              SamplerInterpolationSystem, SamplerProcessor};
 
 /// Bundle for only the sampler interpolation.
 ///
@@ -13,6 +18,7 @@ use systems::{AnimationControlSystem, AnimationProcessor, SamplerInterpolationSy
 #[derive(Default)]
 pub struct SamplingBundle<'a> {
     dep: &'a [&'a str],
+    clock: Clock,
 }
 
 impl<'a> SamplingBundle<'a> {
@@ -26,6 +32,12 @@ impl<'a> SamplingBundle<'a> {
         self.dep = dep;
         self
     }
+
+    /// Set the clock the `SamplerInterpolationSystem` follows, defaults to game time
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
 }
 
 impl<'a, 'b, 'c> ECSBundle<'a, 'b> for SamplingBundle<'c> {
@@ -34,6 +46,15 @@ impl<'a, 'b, 'c> ECSBundle<'a, 'b> for SamplingBundle<'c> {
         world: &mut World,
         builder: DispatcherBuilder<'a, 'b>,
     ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
         world.add_resource(AssetStorage::<Sampler>::new());
         world.register::<SamplerControlSet>();
 
@@ -41,12 +62,27 @@ impl<'a, 'b, 'c> ECSBundle<'a, 'b> for SamplingBundle<'c> {
             builder
                 .add(SamplerProcessor::new(), "sampler_processor", &[])
                 .add(
-                    SamplerInterpolationSystem::new(),
+                    SamplerInterpolationSystem::new().with_clock(self.clock),
                     "sampler_interpolation_system",
                     self.dep,
                 ),
         )
     }
+
+    fn name(&self) -> &str {
+        "sampling"
+    }
+
+    fn required_systems(&self) -> Vec<String> {
+        self.dep.iter().map(|dep| dep.to_string()).collect()
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        vec![
+            "sampler_processor".to_owned(),
+            "sampler_interpolation_system".to_owned(),
+        ]
+    }
 }
 
 /// Bundle for a complete animation setup including sampler interpolation and animation control.
@@ -57,6 +93,7 @@ impl<'a, 'b, 'c> ECSBundle<'a, 'b> for SamplingBundle<'c> {
 #[derive(Default)]
 pub struct AnimationBundle<'a> {
     dep: &'a [&'a str],
+    clock: Clock,
 }
 
 impl<'a> AnimationBundle<'a> {
@@ -70,14 +107,29 @@ impl<'a> AnimationBundle<'a> {
         self.dep = dep;
         self
     }
+
+    /// Set the clock the animations follow, defaults to game time
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
 }
 
 impl<'a, 'b, 'c> ECSBundle<'a, 'b> for AnimationBundle<'c> {
     fn build(
         self,
         world: &mut World,
-        mut builder: DispatcherBuilder<'a, 'b>,
+        builder: DispatcherBuilder<'a, 'b>,
     ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        mut builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
         world.add_resource(AssetStorage::<Animation>::new());
         world.register::<AnimationControl>();
         world.register::<AnimationHierarchy>();
@@ -91,6 +143,175 @@ impl<'a, 'b, 'c> ECSBundle<'a, 'b> for AnimationBundle<'c> {
             );
         SamplingBundle::new()
             .with_dep(&["animation_control_system"])
+            .with_clock(self.clock)
             .build(world, builder)
     }
+
+    fn name(&self) -> &str {
+        "animation"
+    }
+
+    fn required_systems(&self) -> Vec<String> {
+        self.dep.iter().map(|dep| dep.to_string()).collect()
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        vec![
+            "animation_processor".to_owned(),
+            "animation_control_system".to_owned(),
+            "sampler_processor".to_owned(),
+            "sampler_interpolation_system".to_owned(),
+        ]
+    }
+}
+
+/// Bundle for tweening the `C` components.
+///
+/// Registers `Tween<C>`, but not `C`, adds the `EventChannel<TweenCompleted>` resource if it
+/// doesn't exist yet, and adds a `TweenSystem<C>` with the given name. Add one bundle for every
+/// tweened component type.
+pub struct TweenBundle<'a, C> {
+    name: &'a str,
+    dep: &'a [&'a str],
+    clock: Clock,
+    marker: PhantomData<fn() -> C>,
+}
+
+impl<'a, C> TweenBundle<'a, C> {
+    /// Create a new tween bundle, `name` is the name of the system.
+    pub fn new(name: &'a str) -> Self {
+        TweenBundle {
+            name,
+            dep: &[],
+            clock: Clock::default(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Set dependencies for the `TweenSystem`
+    pub fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
+        self.dep = dep;
+        self
+    }
+
+    /// Set the clock the tweens follow, defaults to game time
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+impl<'a, 'b, 'c, C: Tweenable> ECSBundle<'a, 'b> for TweenBundle<'c, C> {
+    fn build(
+        self,
+        world: &mut World,
+        builder: DispatcherBuilder<'a, 'b>,
+    ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
+        world.register::<Tween<C>>();
+        if Option::<Fetch<EventChannel<TweenCompleted>>>::fetch(&world.res, 0).is_none() {
+            world.add_resource(EventChannel::<TweenCompleted>::new());
+        }
+
+        Ok(builder.add(
+            TweenSystem::<C>::new().with_clock(self.clock),
+            self.name,
+            self.dep,
+        ))
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn required_systems(&self) -> Vec<String> {
+        self.dep.iter().map(|dep| dep.to_string()).collect()
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        vec![self.name.to_owned()]
+    }
+}
+
+/// Bundle for moving entities along paths.
+///
+/// Adds the `AssetStorage<Path>` resource, registers `FollowPath`, and will add
+/// `PathProcessor` with name `path_processor` and `FollowPathSystem` with name
+/// `follow_path_system`.
+#[derive(Default)]
+pub struct PathBundle<'a> {
+    dep: &'a [&'a str],
+    clock: Clock,
+}
+
+impl<'a> PathBundle<'a> {
+    /// Create a new path bundle
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set dependencies for the `FollowPathSystem`
+    pub fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
+        self.dep = dep;
+        self
+    }
+
+    /// Set the clock the entities move with, defaults to game time
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+impl<'a, 'b, 'c> ECSBundle<'a, 'b> for PathBundle<'c> {
+    fn build(
+        self,
+        world: &mut World,
+        builder: DispatcherBuilder<'a, 'b>,
+    ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
+        world.add_resource(AssetStorage::<Path>::new());
+        world.register::<FollowPath>();
+
+        Ok(
+            builder
+                .add(PathProcessor::new(), "path_processor", &[])
+                .add(
+                    FollowPathSystem::new().with_clock(self.clock),
+                    "follow_path_system",
+                    self.dep,
+                ),
+        )
+    }
+
+    fn name(&self) -> &str {
+        "path"
+    }
+
+    fn required_systems(&self) -> Vec<String> {
+        self.dep.iter().map(|dep| dep.to_string()).collect()
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        vec![
+            "path_processor".to_owned(),
+            "follow_path_system".to_owned(),
+        ]
+    }
 }
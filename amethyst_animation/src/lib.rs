@@ -1,22 +1,34 @@
 extern crate amethyst_assets;
 extern crate amethyst_core;
 extern crate fnv;
+#[macro_use]
+extern crate log;
 extern crate minterpolate;
+#[cfg(test)]
+extern crate rayon;
+extern crate ron;
 #[macro_use]
 extern crate serde;
+extern crate shrev;
 extern crate specs;
 
-pub use self::bundle::{AnimationBundle, SamplingBundle};
+pub use self::bundle::{AnimationBundle, PathBundle, SamplingBundle, TweenBundle};
 pub use self::interpolation::{Interpolate, InterpolationFunction, InterpolationType};
+pub use self::path::{FollowPath, FollowPathSystem, Path, PathData, PathFormat, PathMode,
+                     PathSegment};
 pub use self::resources::{Animation, AnimationCommand, AnimationControl, AnimationHierarchy,
                           AnimationOutput, AnimationSet, ControlState, EndControl, RestState,
                           Sampler, SamplerControl, SamplerControlSet};
-pub use self::systems::{AnimationControlSystem, AnimationProcessor, SamplerInterpolationSystem,
-                        SamplerProcessor};
+pub use self::systems::{AnimationControlSystem, AnimationProcessor, PathProcessor,
+                        SamplerInterpolationSystem, SamplerProcessor};
+pub use self::tween::{Easing, TransformTween, Tween, TweenCompleted, TweenRepeat, TweenSystem,
+                      Tweenable};
 pub use self::util::*;
 
 mod resources;
 mod systems;
 mod interpolation;
 mod bundle;
+mod path;
+mod tween;
 mod util;
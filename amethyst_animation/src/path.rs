@@ -0,0 +1,486 @@
+//! Spline paths, and entities moving along them.
+
+use amethyst_assets::{Asset, AssetStorage, Handle, Result, ResultExt, SimpleFormat};
+use amethyst_core::{duration_to_secs, Clock, LocalTransform, Time};
+use amethyst_core::cgmath::{EuclideanSpace, InnerSpace, Point3, Vector3, Zero};
+use amethyst_core::orientation::Orientation;
+use specs::{Component, DenseVecStorage, Fetch, Join, System, WriteStorage};
+
+/// The number of pieces every curve is split into to measure its length.
+const SAMPLES: usize = 16;
+
+/// A segment of a path, going from the end of the previous segment to a new point.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum PathSegment {
+    /// A straight line to the point.
+    Linear([f32; 3]),
+    /// A Catmull-Rom spline passing through all the points. The tangents at its ends point
+    /// to the neighbouring points.
+    CatmullRom(Vec<[f32; 3]>),
+    /// A cubic Bezier curve to `end`, pulled towards the two control points.
+    Bezier {
+        /// The control point after the start of the segment
+        control_1: [f32; 3],
+        /// The control point before the end of the segment
+        control_2: [f32; 3],
+        /// The end of the segment
+        end: [f32; 3],
+    },
+}
+
+/// The data of a path, as loaded from a RON file.
+///
+/// ```ron
+/// (
+///     start: (0.0, 0.0, 0.0),
+///     segments: [
+///         Linear((2.0, 0.0, 0.0)),
+///         CatmullRom([(3.0, 1.0, 0.0), (2.0, 2.0, 0.0), (0.0, 2.0, 0.0)]),
+///         Bezier(
+///             control_1: (-1.0, 2.0, 0.0),
+///             control_2: (-1.0, 0.0, 0.0),
+///             end: (0.0, 0.0, 0.0),
+///         ),
+///     ],
+/// )
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PathData {
+    /// The start of the first segment
+    pub start: [f32; 3],
+    /// The segments, in order
+    pub segments: Vec<PathSegment>,
+}
+
+impl Into<Result<Path>> for PathData {
+    fn into(self) -> Result<Path> {
+        Ok(Path::from(self))
+    }
+}
+
+/// A cubic Bezier curve, with the cumulative length at every sample.
+#[derive(Clone, Debug, PartialEq)]
+struct Curve {
+    points: [Vector3<f32>; 4],
+    start: f32,
+    lengths: [f32; SAMPLES + 1],
+}
+
+impl Curve {
+    fn new(points: [Vector3<f32>; 4], start: f32) -> Self {
+        let mut curve = Curve {
+            points,
+            start,
+            lengths: [0.; SAMPLES + 1],
+        };
+        let mut previous = curve.point(0.);
+        for i in 1..SAMPLES + 1 {
+            let point = curve.point(i as f32 / SAMPLES as f32);
+            curve.lengths[i] = curve.lengths[i - 1] + (point - previous).magnitude();
+            previous = point;
+        }
+        curve
+    }
+
+    fn length(&self) -> f32 {
+        self.lengths[SAMPLES]
+    }
+
+    fn point(&self, t: f32) -> Vector3<f32> {
+        let p = &self.points;
+        let u = 1. - t;
+        p[0] * (u * u * u) + p[1] * (3. * u * u * t) + p[2] * (3. * u * t * t)
+            + p[3] * (t * t * t)
+    }
+
+    fn derivative(&self, t: f32) -> Vector3<f32> {
+        let p = &self.points;
+        let u = 1. - t;
+        (p[1] - p[0]) * (3. * u * u) + (p[2] - p[1]) * (6. * u * t)
+            + (p[3] - p[2]) * (3. * t * t)
+    }
+
+    /// Finds the curve parameter for a distance from the start of the curve.
+    fn parameter(&self, distance: f32) -> f32 {
+        for i in 0..SAMPLES {
+            let (from, to) = (self.lengths[i], self.lengths[i + 1]);
+            if distance <= to {
+                let fraction = if to > from {
+                    (distance - from) / (to - from)
+                } else {
+                    0.
+                };
+                return (i as f32 + fraction.max(0.)) / SAMPLES as f32;
+            }
+        }
+        1.
+    }
+}
+
+/// A path made of linear, Catmull-Rom and Bezier segments, see `PathData`.
+///
+/// Positions on the path are given as the distance along the path from its start.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    start: Vector3<f32>,
+    curves: Vec<Curve>,
+}
+
+impl Path {
+    /// The total length of the path.
+    pub fn length(&self) -> f32 {
+        self.curves.last().map_or(0., |curve| curve.start + curve.length())
+    }
+
+    /// Returns the point at `distance` along the path, clamped to the ends of the path.
+    pub fn point_at(&self, distance: f32) -> Vector3<f32> {
+        match self.locate(distance) {
+            Some((curve, t)) => curve.point(t),
+            None => self.start,
+        }
+    }
+
+    /// Returns the normalized direction of the path at `distance`, or zero if the path has no
+    /// direction there.
+    pub fn tangent_at(&self, distance: f32) -> Vector3<f32> {
+        let tangent = match self.locate(distance) {
+            Some((curve, t)) => curve.derivative(t),
+            None => return Vector3::zero(),
+        };
+        if tangent.magnitude2() > 0. {
+            tangent.normalize()
+        } else {
+            tangent
+        }
+    }
+
+    fn locate(&self, distance: f32) -> Option<(&Curve, f32)> {
+        let distance = distance.max(0.).min(self.length());
+        self.curves
+            .iter()
+            .find(|curve| distance <= curve.start + curve.length())
+            .map(|curve| (curve, curve.parameter(distance - curve.start)))
+    }
+}
+
+impl From<PathData> for Path {
+    fn from(data: PathData) -> Self {
+        let start = Vector3::from(data.start);
+        let mut points = Vec::new();
+        let mut end = start;
+
+        for segment in data.segments {
+            match segment {
+                PathSegment::Linear(to) => {
+                    let to = Vector3::from(to);
+                    let step = (to - end) / 3.;
+                    points.push([end, end + step, to - step, to]);
+                    end = to;
+                }
+                PathSegment::CatmullRom(through) => {
+                    let mut spline = vec![end];
+                    spline.extend(through.into_iter().map(Vector3::from));
+                    let last = spline.len() - 1;
+                    for i in 0..last {
+                        let before = spline[i.saturating_sub(1)];
+                        let after = spline[(i + 2).min(last)];
+                        let (from, to) = (spline[i], spline[i + 1]);
+                        // Converts the uniform Catmull-Rom span to the equivalent Bezier curve.
+                        // The splines of minterpolate are sampled over keyframe times, and give
+                        // neither the derivative nor control points to measure the length with,
+                        // so the span is measured and sampled like the other segments.
+                        let control_1 = from + (to - before) / 6.;
+                        let control_2 = to - (after - from) / 6.;
+                        points.push([from, control_1, control_2, to]);
+                    }
+                    end = spline[last];
+                }
+                PathSegment::Bezier {
+                    control_1,
+                    control_2,
+                    end: to,
+                } => {
+                    let to = Vector3::from(to);
+                    points.push([end, Vector3::from(control_1), Vector3::from(control_2), to]);
+                    end = to;
+                }
+            }
+        }
+
+        let mut curves: Vec<Curve> = Vec::with_capacity(points.len());
+        for points in points {
+            let start = curves.last().map_or(0., |curve| curve.start + curve.length());
+            curves.push(Curve::new(points, start));
+        }
+
+        Path { start, curves }
+    }
+}
+
+impl Asset for Path {
+    type Data = PathData;
+    type HandleStorage = DenseVecStorage<Handle<Self>>;
+}
+
+/// Loads a `Path` from a RON file, see `PathData` for the layout.
+#[derive(Clone, Debug)]
+pub struct PathFormat;
+
+impl SimpleFormat<Path> for PathFormat {
+    const NAME: &'static str = "PATH";
+
+    type Options = ();
+
+    fn import(&self, bytes: Vec<u8>, _: ()) -> Result<PathData> {
+        use ron::de::from_str;
+        use std::str::from_utf8;
+
+        let s = from_utf8(&bytes)?;
+
+        from_str(s).chain_err(|| "Failed to decode path file")
+    }
+}
+
+/// What happens when a `FollowPath` reaches the end of its path.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum PathMode {
+    /// Stops at the end.
+    Once,
+    /// Jumps back to the start.
+    Loop,
+    /// Turns around, and goes back and forth between the ends.
+    PingPong,
+}
+
+impl Default for PathMode {
+    fn default() -> Self {
+        PathMode::Once
+    }
+}
+
+/// Moves the entity along a `Path`, by setting the translation of its `LocalTransform`.
+///
+/// The path is in the coordinate space of the parent of the entity, if it has one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FollowPath {
+    /// The followed path
+    pub path: Handle<Path>,
+    /// The speed in units per second, negative speeds move towards the start of the path
+    pub speed: f32,
+    /// What happens at the end of the path
+    pub mode: PathMode,
+    /// Rotate the entity to face along the path, see `with_orientation`
+    pub orient: bool,
+    travelled: f32,
+    finished: bool,
+}
+
+impl FollowPath {
+    /// Creates a new component following `path` from its start at `speed` units per second.
+    pub fn new(path: Handle<Path>, speed: f32) -> Self {
+        FollowPath {
+            path,
+            speed,
+            mode: PathMode::Once,
+            orient: false,
+            travelled: 0.,
+            finished: false,
+        }
+    }
+
+    /// Set what happens at the end of the path, defaults to `PathMode::Once`.
+    pub fn with_mode(mut self, mode: PathMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Rotate the entity to face the direction it moves in, without rolling, using the
+    /// `Orientation` resource. Defaults to false.
+    pub fn with_orientation(mut self, orient: bool) -> Self {
+        self.orient = orient;
+        self
+    }
+
+    /// Set the distance along the path the entity starts at.
+    pub fn with_distance(mut self, distance: f32) -> Self {
+        self.travelled = distance;
+        self
+    }
+
+    /// The distance travelled since the start of the path. In `PathMode::PingPong` it
+    /// includes the way back, and goes up to twice the length of the path.
+    pub fn travelled(&self) -> f32 {
+        self.travelled
+    }
+
+    /// Returns true if the entity reached the end of the path in `PathMode::Once`.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Advances by `delta` seconds on a path of `length`, returning the new distance along
+    /// the path and whether the entity moves towards the start of the path.
+    fn advance(&mut self, delta: f32, length: f32) -> (f32, bool) {
+        let backwards = self.speed < 0.;
+        if length <= 0. {
+            return (0., backwards);
+        }
+
+        let travelled = self.travelled + self.speed * delta;
+        match self.mode {
+            PathMode::Once => {
+                self.travelled = travelled.max(0.).min(length);
+                self.finished = if backwards {
+                    self.travelled <= 0.
+                } else {
+                    self.travelled >= length
+                };
+                (self.travelled, backwards)
+            }
+            PathMode::Loop => {
+                self.travelled = (travelled % length + length) % length;
+                (self.travelled, backwards)
+            }
+            PathMode::PingPong => {
+                let cycle = 2. * length;
+                self.travelled = (travelled % cycle + cycle) % cycle;
+                if self.travelled > length {
+                    (cycle - self.travelled, !backwards)
+                } else {
+                    (self.travelled, backwards)
+                }
+            }
+        }
+    }
+}
+
+impl Component for FollowPath {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Moves the entities with a `FollowPath` along their paths.
+///
+/// Entities whose path isn't loaded yet stay where they are.
+#[derive(Default)]
+pub struct FollowPathSystem {
+    clock: Clock,
+}
+
+impl FollowPathSystem {
+    /// Creates a new path following system.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the clock used to move the entities, defaults to game time.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+impl<'a> System<'a> for FollowPathSystem {
+    type SystemData = (
+        Fetch<'a, Time>,
+        Fetch<'a, AssetStorage<Path>>,
+        Option<Fetch<'a, Orientation>>,
+        WriteStorage<'a, FollowPath>,
+        WriteStorage<'a, LocalTransform>,
+    );
+
+    fn run(&mut self, (time, paths, orientation, mut follows, mut locals): Self::SystemData) {
+        let default_orientation = Orientation::default();
+        let orientation = orientation.as_ref().map_or(&default_orientation, |o| &**o);
+        let delta = duration_to_secs(time.delta_time_of(self.clock));
+
+        for (follow, local) in (&mut follows, &mut locals).join() {
+            let path = match paths.get(&follow.path) {
+                Some(path) => path,
+                None => continue,
+            };
+
+            let (distance, backwards) = follow.advance(delta, path.length());
+            local.translation = path.point_at(distance);
+
+            if follow.orient {
+                let tangent = path.tangent_at(distance);
+                let direction = if backwards { -tangent } else { tangent };
+                if direction != Vector3::zero() {
+                    let target = Point3::from_vec(local.translation + direction);
+                    local.look_at(orientation, target);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use amethyst_assets::Loader;
+    use rayon::{Configuration, ThreadPool};
+
+    use super::*;
+
+    fn follow(speed: f32, mode: PathMode) -> FollowPath {
+        let pool = Arc::new(ThreadPool::new(Configuration::new()).unwrap());
+        let storage = AssetStorage::<Path>::new();
+        let path = Loader::new(".", pool).load_from_data(PathData::default(), (), &storage);
+        FollowPath::new(path, speed).with_mode(mode)
+    }
+
+    fn assert_near(left: Vector3<f32>, right: Vector3<f32>) {
+        assert!((left - right).magnitude() < 1.0e-4, "{:?} != {:?}", left, right);
+    }
+
+    #[test]
+    fn linear_path() {
+        let path = Path::from(PathData {
+            start: [0.0, 0.0, 0.0],
+            segments: vec![
+                PathSegment::Linear([4.0, 0.0, 0.0]),
+                PathSegment::Linear([4.0, 3.0, 0.0]),
+            ],
+        });
+
+        assert!((path.length() - 7.0).abs() < 1.0e-4);
+        assert_near(path.point_at(3.5), Vector3::new(3.5, 0.0, 0.0));
+        assert_near(path.point_at(5.5), Vector3::new(4.0, 1.5, 0.0));
+        assert_near(path.point_at(-1.0), Vector3::new(0.0, 0.0, 0.0));
+        assert_near(path.point_at(8.0), Vector3::new(4.0, 3.0, 0.0));
+        assert_near(path.tangent_at(2.0), Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn advance_loop() {
+        let mut follow = follow(4.0, PathMode::Loop);
+        assert_eq!(follow.advance(2.0, 10.0), (8.0, false));
+        assert_eq!(follow.advance(1.0, 10.0), (2.0, false));
+        assert!(!follow.is_finished());
+    }
+
+    #[test]
+    fn advance_ping_pong() {
+        let mut follow = follow(4.0, PathMode::PingPong);
+        assert_eq!(follow.advance(2.0, 10.0), (8.0, false));
+        assert_eq!(follow.advance(1.0, 10.0), (8.0, true));
+        assert_eq!(follow.travelled(), 12.0);
+        assert_eq!(follow.advance(2.0, 10.0), (0.0, false));
+    }
+
+    #[test]
+    fn advance_backwards() {
+        let mut once = follow(-4.0, PathMode::Once).with_distance(6.0);
+        assert_eq!(once.advance(1.0, 10.0), (2.0, true));
+        assert!(!once.is_finished());
+        assert_eq!(once.advance(1.0, 10.0), (0.0, true));
+        assert!(once.is_finished());
+
+        let mut looping = follow(-4.0, PathMode::Loop);
+        assert_eq!(looping.advance(1.0, 10.0), (6.0, true));
+
+        let mut ping_pong = follow(-4.0, PathMode::PingPong);
+        assert_eq!(ping_pong.advance(1.0, 10.0), (4.0, false));
+    }
+}
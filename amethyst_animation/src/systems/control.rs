@@ -128,7 +128,7 @@ fn process_animation_control(
         None => if only_one_index(&animation.nodes) {
             &h_fallback
         } else {
-            eprintln!(
+            warn!(
                 "Animation control which target multiple nodes without a hierarchy detected, dropping"
             );
             remove.push(*entity);
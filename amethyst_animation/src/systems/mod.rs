@@ -3,6 +3,7 @@ pub use self::sampling::SamplerInterpolationSystem;
 
 use amethyst_assets::Processor;
 
+use path::Path;
 use resources::{Animation, Sampler};
 
 mod sampling;
@@ -13,3 +14,6 @@ pub type SamplerProcessor = Processor<Sampler>;
 
 /// Asset storage processor for `Animation`
 pub type AnimationProcessor = Processor<Animation>;
+
+/// Asset storage processor for `Path`
+pub type PathProcessor = Processor<Path>;
@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use amethyst_assets::AssetStorage;
 use amethyst_core::{duration_to_nanos, duration_to_secs, nanos_to_duration, secs_to_duration,
-                    LocalTransform, Time};
+                    Clock, LocalTransform, Time};
 use specs::{Fetch, Join, System, WriteStorage};
 
 use interpolation::Interpolate;
@@ -15,13 +15,24 @@ use resources::{ControlState, EndControl, RestState, Sampler, SamplerControl, Sa
 ///
 /// Will process all active `SamplerControlSet`, and update the `LocalTransform` for the entity they
 /// belong to.
+///
+/// Samplers advance with game time by default, so they follow the time scale and stop while the
+/// game is paused. Use `with_clock(Clock::Real)` for animations that should keep playing, like UI.
 #[derive(Default)]
-pub struct SamplerInterpolationSystem;
+pub struct SamplerInterpolationSystem {
+    clock: Clock,
+}
 
 impl SamplerInterpolationSystem {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Set the clock used to advance the samplers.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
 }
 
 impl<'a> System<'a> for SamplerInterpolationSystem {
@@ -33,27 +44,28 @@ impl<'a> System<'a> for SamplerInterpolationSystem {
     );
 
     fn run(&mut self, (time, samplers, mut controls, mut transforms): Self::SystemData) {
+        let delta = time.delta_time_of(self.clock);
         for (control_set, transform) in (&mut controls, &mut transforms).join() {
             if let Some((ref mut control, sampler)) = control_set
                 .translation
                 .as_mut()
                 .and_then(|c| samplers.get(&c.sampler).and_then(|s| Some((c, s))))
             {
-                process_sampler(control, sampler, transform, &time);
+                process_sampler(control, sampler, transform, delta);
             }
             if let Some((ref mut control, sampler)) = control_set
                 .rotation
                 .as_mut()
                 .and_then(|c| samplers.get(&c.sampler).and_then(|s| Some((c, s))))
             {
-                process_sampler(control, sampler, transform, &time);
+                process_sampler(control, sampler, transform, delta);
             }
             if let Some((ref mut control, sampler)) = control_set
                 .scale
                 .as_mut()
                 .and_then(|c| samplers.get(&c.sampler).and_then(|s| Some((c, s))))
             {
-                process_sampler(control, sampler, transform, &time);
+                process_sampler(control, sampler, transform, delta);
             }
         }
     }
@@ -66,16 +78,16 @@ impl<'a> System<'a> for SamplerInterpolationSystem {
 /// - `control`: sampler control object
 /// - `sampler`: the sampler reference from the control object
 /// - `transform`: the `LocalTransform` to update
-/// - `now`: synchronized `Instant` for the current frame
+/// - `delta`: time elapsed since the last frame on the clock of the system
 fn process_sampler(
     control: &mut SamplerControl,
     sampler: &Sampler,
     transform: &mut LocalTransform,
-    time: &Time,
+    delta: Duration,
 ) {
     use resources::ControlState::*;
 
-    let (new_state, new_end) = update_duration_and_check(&control, sampler, time);
+    let (new_state, new_end) = update_duration_and_check(&control, sampler, delta);
 
     // If a new end condition has been computed, update in control state
     if let Some(end) = new_end {
@@ -115,7 +127,7 @@ fn do_end_control(control: &SamplerControl, transform: &mut LocalTransform) {
 ///
 /// - `control`: sampler control object
 /// - `sampler`: sampler reference from control
-/// - `now`: synchronized `Instant` for the current frame
+/// - `delta`: time elapsed since the last frame on the clock of the system
 ///
 /// ## Returns
 ///
@@ -123,7 +135,7 @@ fn do_end_control(control: &SamplerControl, transform: &mut LocalTransform) {
 fn update_duration_and_check(
     control: &SamplerControl,
     sampler: &Sampler,
-    time: &Time,
+    delta: Duration,
 ) -> (ControlState, Option<EndControl>) {
     use resources::ControlState::*;
     // Update state with new duration
@@ -138,7 +150,7 @@ fn update_duration_and_check(
         // sampling is running, update duration and check end condition
         Running(duration) => {
             let zero = Duration::from_secs(0);
-            let current_dur = duration + time.delta_time();
+            let current_dur = duration + delta;
             let last_frame = sampler
                 .input
                 .last()
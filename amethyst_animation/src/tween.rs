@@ -0,0 +1,463 @@
+//! Tweening of single component properties from a start to an end value.
+
+use std::f32::consts::PI;
+use std::fmt;
+use std::marker::PhantomData;
+
+use amethyst_core::{duration_to_secs, Clock, LocalTransform, Time};
+use amethyst_core::cgmath::{Quaternion, Vector3};
+use shrev::EventChannel;
+use specs::{Component, DenseVecStorage, Entities, Entity, Fetch, FetchMut, Join, System,
+            WriteStorage};
+
+/// Easing curves, mapping the linear progress of a tween to the progress of its value.
+///
+/// The `In` curves start slow, the `Out` curves end slow, and the `InOut` curves do both.
+/// `Elastic`, `Bounce` and `Back` overshoot the end or start value.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Quadratic acceleration
+    QuadIn,
+    /// Quadratic deceleration
+    QuadOut,
+    /// Quadratic acceleration and deceleration
+    QuadInOut,
+    /// Cubic acceleration
+    CubicIn,
+    /// Cubic deceleration
+    CubicOut,
+    /// Cubic acceleration and deceleration
+    CubicInOut,
+    /// Growing oscillation at the start
+    ElasticIn,
+    /// Decaying oscillation at the end
+    ElasticOut,
+    /// Oscillation at the start and the end
+    ElasticInOut,
+    /// Bounces off the start value
+    BounceIn,
+    /// Bounces off the end value
+    BounceOut,
+    /// Bounces off the start and end value
+    BounceInOut,
+    /// Pulls back before moving
+    BackIn,
+    /// Overshoots the end value, then settles
+    BackOut,
+    /// Pulls back and overshoots
+    BackInOut,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::Linear
+    }
+}
+
+impl Easing {
+    /// Maps the linear progress `t` in `[0, 1]` to the eased progress.
+    ///
+    /// The result is 0 for `t = 0` and 1 for `t = 1`, but can leave that range in between.
+    pub fn apply(&self, t: f32) -> f32 {
+        use self::Easing::*;
+
+        const BACK: f32 = 1.70158;
+        const BACK_IN_OUT: f32 = BACK * 1.525;
+
+        let t = t.max(0.).min(1.);
+        match *self {
+            Linear => t,
+            QuadIn => t * t,
+            QuadOut => t * (2. - t),
+            QuadInOut => if t < 0.5 {
+                2. * t * t
+            } else {
+                -1. + (4. - 2. * t) * t
+            },
+            CubicIn => t * t * t,
+            CubicOut => (t - 1.).powi(3) + 1.,
+            CubicInOut => if t < 0.5 {
+                4. * t * t * t
+            } else {
+                (t - 1.) * (2. * t - 2.) * (2. * t - 2.) + 1.
+            },
+            ElasticIn => if t == 0. || t == 1. {
+                t
+            } else {
+                -(2f32).powf(10. * t - 10.) * ((10. * t - 10.75) * 2. * PI / 3.).sin()
+            },
+            ElasticOut => if t == 0. || t == 1. {
+                t
+            } else {
+                (2f32).powf(-10. * t) * ((10. * t - 0.75) * 2. * PI / 3.).sin() + 1.
+            },
+            ElasticInOut => if t == 0. || t == 1. {
+                t
+            } else {
+                let wave = ((20. * t - 11.125) * 2. * PI / 4.5).sin();
+                if t < 0.5 {
+                    -(2f32).powf(20. * t - 10.) * wave / 2.
+                } else {
+                    (2f32).powf(-20. * t + 10.) * wave / 2. + 1.
+                }
+            },
+            BounceIn => 1. - bounce_out(1. - t),
+            BounceOut => bounce_out(t),
+            BounceInOut => if t < 0.5 {
+                (1. - bounce_out(1. - 2. * t)) / 2.
+            } else {
+                (1. + bounce_out(2. * t - 1.)) / 2.
+            },
+            BackIn => (BACK + 1.) * t * t * t - BACK * t * t,
+            BackOut => {
+                let t = t - 1.;
+                1. + (BACK + 1.) * t * t * t + BACK * t * t
+            }
+            BackInOut => if t < 0.5 {
+                let t = 2. * t;
+                t * t * ((BACK_IN_OUT + 1.) * t - BACK_IN_OUT) / 2.
+            } else {
+                let t = 2. * t - 2.;
+                (t * t * ((BACK_IN_OUT + 1.) * t + BACK_IN_OUT) + 2.) / 2.
+            },
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1. / D {
+        N * t * t
+    } else if t < 2. / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// How often a tween runs.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum TweenRepeat {
+    /// Runs a single time.
+    Once,
+    /// Runs the given number of times.
+    Times(u32),
+    /// Runs until the `Tween` is removed.
+    Forever,
+}
+
+impl Default for TweenRepeat {
+    fn default() -> Self {
+        TweenRepeat::Once
+    }
+}
+
+/// A component which can be tweened by a `TweenSystem`.
+pub trait Tweenable: Component {
+    /// The tweened property, with its start and end value.
+    type Property: Clone + fmt::Debug + Send + Sync + 'static;
+
+    /// Sets the property to the value at `t`, going from 0 at the start value to 1 at the
+    /// end value. Easings can make `t` leave this range.
+    fn tween(&mut self, property: &Self::Property, t: f32);
+}
+
+/// Tweens a property of the `C` component of the same entity.
+///
+/// An entity can only have one `Tween<C>` at a time, the `TweenSystem<C>` removes it when it
+/// completes and writes a `TweenCompleted` event.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate amethyst_animation;
+/// # extern crate amethyst_core;
+/// use amethyst_animation::{Easing, TransformTween, Tween, TweenRepeat};
+/// use amethyst_core::LocalTransform;
+/// use amethyst_core::cgmath::Vector3;
+///
+/// # fn main() {
+/// let tween = Tween::<LocalTransform>::new(
+///     TransformTween::Translation(Vector3::new(0., 0., 0.), Vector3::new(0., 5., 0.)),
+///     1.5,
+/// ).with_easing(Easing::BounceOut)
+///     .with_repeat(TweenRepeat::Forever)
+///     .with_yoyo(true);
+/// # }
+/// ```
+pub struct Tween<C: Tweenable> {
+    property: C::Property,
+    duration: f32,
+    easing: Easing,
+    repeat: TweenRepeat,
+    yoyo: bool,
+    elapsed: f32,
+}
+
+impl<C: Tweenable> Tween<C> {
+    /// Creates a new tween of `property`, taking `duration` seconds to reach its end value.
+    pub fn new(property: C::Property, duration: f32) -> Self {
+        Tween {
+            property,
+            duration,
+            easing: Easing::Linear,
+            repeat: TweenRepeat::Once,
+            yoyo: false,
+            elapsed: 0.,
+        }
+    }
+
+    /// Set the easing curve, defaults to `Easing::Linear`.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Set how often the tween runs, defaults to `TweenRepeat::Once`.
+    pub fn with_repeat(mut self, repeat: TweenRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Go back to the start value after reaching the end value, in the same duration.
+    /// Every run of the tween includes the way back.
+    pub fn with_yoyo(mut self, yoyo: bool) -> Self {
+        self.yoyo = yoyo;
+        self
+    }
+
+    /// The tweened property.
+    pub fn property(&self) -> &C::Property {
+        &self.property
+    }
+
+    /// The time in seconds since the tween started.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Advances the tween by `delta` seconds, returning the linear progress and whether
+    /// the tween completed.
+    fn advance(&mut self, delta: f32) -> (f32, bool) {
+        self.elapsed += delta;
+
+        let run = if self.yoyo {
+            2. * self.duration
+        } else {
+            self.duration
+        };
+        let runs = match self.repeat {
+            TweenRepeat::Once => Some(1),
+            TweenRepeat::Times(runs) => Some(runs),
+            TweenRepeat::Forever => None,
+        };
+
+        match runs {
+            Some(runs) if self.elapsed >= run * runs as f32 => {
+                return (if self.yoyo { 0. } else { 1. }, true)
+            }
+            _ if run <= 0. => return (1., false),
+            _ => {}
+        }
+
+        let position = self.elapsed % run;
+        if position > self.duration {
+            (2. - position / self.duration, false)
+        } else {
+            (position / self.duration, false)
+        }
+    }
+}
+
+impl<C: Tweenable> Clone for Tween<C> {
+    fn clone(&self) -> Self {
+        Tween {
+            property: self.property.clone(),
+            ..*self
+        }
+    }
+}
+
+impl<C: Tweenable> fmt::Debug for Tween<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tween")
+            .field("property", &self.property)
+            .field("duration", &self.duration)
+            .field("easing", &self.easing)
+            .field("repeat", &self.repeat)
+            .field("yoyo", &self.yoyo)
+            .field("elapsed", &self.elapsed)
+            .finish()
+    }
+}
+
+impl<C: Tweenable> Component for Tween<C> {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Written to the `EventChannel<TweenCompleted>` resource when a tween completes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TweenCompleted {
+    /// The entity which had the `Tween`.
+    pub entity: Entity,
+}
+
+/// The tweenable properties of `LocalTransform`, with their start and end values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransformTween {
+    /// Tweens the translation.
+    Translation(Vector3<f32>, Vector3<f32>),
+    /// Tweens the rotation, using spherical linear interpolation.
+    Rotation(Quaternion<f32>, Quaternion<f32>),
+    /// Tweens the scale.
+    Scale(Vector3<f32>, Vector3<f32>),
+}
+
+impl Tweenable for LocalTransform {
+    type Property = TransformTween;
+
+    fn tween(&mut self, property: &TransformTween, t: f32) {
+        match *property {
+            TransformTween::Translation(start, end) => {
+                self.translation = start + (end - start) * t;
+            }
+            TransformTween::Rotation(start, end) => {
+                self.rotation = start.slerp(end, t);
+            }
+            TransformTween::Scale(start, end) => {
+                self.scale = start + (end - start) * t;
+            }
+        }
+    }
+}
+
+/// Advances the `Tween<C>` components, and applies them to the `C` components.
+///
+/// Tweens advance with game time by default, use `with_clock(Clock::Real)` for tweens that
+/// should keep running while the game is paused, like UI.
+pub struct TweenSystem<C> {
+    clock: Clock,
+    completed: Vec<Entity>,
+    marker: PhantomData<fn() -> C>,
+}
+
+impl<C> Default for TweenSystem<C> {
+    fn default() -> Self {
+        TweenSystem {
+            clock: Clock::default(),
+            completed: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<C> TweenSystem<C> {
+    /// Creates a new tween system.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the clock used to advance the tweens.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+impl<'a, C: Tweenable> System<'a> for TweenSystem<C> {
+    type SystemData = (
+        Entities<'a>,
+        Fetch<'a, Time>,
+        WriteStorage<'a, Tween<C>>,
+        WriteStorage<'a, C>,
+        FetchMut<'a, EventChannel<TweenCompleted>>,
+    );
+
+    fn run(&mut self, (entities, time, mut tweens, mut components, mut events): Self::SystemData) {
+        let delta = duration_to_secs(time.delta_time_of(self.clock));
+        for (entity, tween, component) in (&*entities, &mut tweens, &mut components).join() {
+            let (t, completed) = tween.advance(delta);
+            component.tween(&tween.property, tween.easing.apply(t));
+            if completed {
+                self.completed.push(entity);
+                events.single_write(TweenCompleted { entity });
+            }
+        }
+
+        for entity in self.completed.drain(..) {
+            tweens.remove(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tween(duration: f32) -> Tween<LocalTransform> {
+        let one = Vector3::new(1., 1., 1.);
+        Tween::new(TransformTween::Scale(one, one * 2.), duration)
+    }
+
+    #[test]
+    fn easing_endpoints() {
+        use super::Easing::*;
+
+        let easings = [
+            Linear,
+            QuadIn,
+            QuadOut,
+            QuadInOut,
+            CubicIn,
+            CubicOut,
+            CubicInOut,
+            ElasticIn,
+            ElasticOut,
+            ElasticInOut,
+            BounceIn,
+            BounceOut,
+            BounceInOut,
+            BackIn,
+            BackOut,
+            BackInOut,
+        ];
+        for easing in &easings {
+            assert!(easing.apply(0.).abs() < 1e-5, "{:?} at 0", easing);
+            assert!((easing.apply(1.) - 1.).abs() < 1e-5, "{:?} at 1", easing);
+        }
+    }
+
+    #[test]
+    fn yoyo() {
+        let mut tween = tween(1.).with_yoyo(true);
+        assert_eq!(tween.advance(0.5), (0.5, false));
+        assert_eq!(tween.advance(1.), (0.5, false));
+        assert_eq!(tween.advance(0.5), (0., true));
+    }
+
+    #[test]
+    fn repeat_times() {
+        let mut tween = tween(1.).with_repeat(TweenRepeat::Times(2));
+        assert_eq!(tween.advance(1.5), (0.5, false));
+        assert_eq!(tween.advance(0.25), (0.75, false));
+        assert_eq!(tween.advance(0.25), (1., true));
+    }
+
+    #[test]
+    fn empty_run() {
+        assert_eq!(tween(0.).advance(0.1), (1., true));
+
+        let mut forever = tween(0.).with_repeat(TweenRepeat::Forever);
+        assert_eq!(forever.advance(0.1), (1., false));
+        assert_eq!(forever.advance(0.1), (1., false));
+    }
+}
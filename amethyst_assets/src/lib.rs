@@ -15,10 +15,15 @@ extern crate crossbeam;
 extern crate derivative;
 #[macro_use]
 extern crate error_chain;
+extern crate erased_serde;
 extern crate fnv;
 extern crate hibitset;
+#[macro_use]
+extern crate log;
 extern crate parking_lot;
 extern crate rayon;
+#[macro_use]
+extern crate serde;
 extern crate specs;
 
 pub use asset::{Asset, Format, FormatValue, SimpleFormat};
@@ -27,6 +32,7 @@ pub use error::{Error, ErrorKind, Result, ResultExt};
 pub use loader::Loader;
 pub use progress::{Completion, Progress, ProgressCounter, Tracker};
 pub use reload::{HotReloadBundle, HotReloadStrategy, HotReloadSystem, Reload, SingleFile};
+pub use save::{AssetPath, HandleEntry};
 pub use source::{Directory, Source};
 pub use storage::{AssetStorage, Handle, Processor, WeakHandle};
 
@@ -36,5 +42,6 @@ mod error;
 mod loader;
 mod progress;
 mod reload;
+mod save;
 mod source;
 mod storage;
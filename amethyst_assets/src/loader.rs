@@ -6,7 +6,7 @@ use std::sync::Arc;
 use fnv::FnvHashMap;
 use rayon::ThreadPool;
 
-use {Asset, Directory, ErrorKind, Format, FormatValue, Progress, ResultExt, Source};
+use {Asset, AssetPath, Directory, ErrorKind, Format, FormatValue, Progress, ResultExt, Source};
 use storage::{AssetStorage, Handle, Processed};
 
 /// The asset loader, holding the sources and a reference to the `ThreadPool`.
@@ -20,6 +20,9 @@ pub struct Loader {
 impl Loader {
     /// Creates a new asset loader, initializing the directory store with the
     /// given path.
+    ///
+    /// Assets are imported on `pool`, which doesn't have to be the pool used
+    /// for system dispatch.
     pub fn new<P>(directory: P, pool: Arc<ThreadPool>) -> Self
     where
         P: Into<PathBuf>,
@@ -106,6 +109,12 @@ impl Loader {
     {
         use progress::Tracker;
 
+        let name = name.into();
+        let path = AssetPath {
+            source: source.as_ref().to_owned(),
+            name: name.clone(),
+        };
+
         let source = match source.as_ref() {
             "" => self.directory.clone(),
             source => self.source(source),
@@ -118,8 +127,6 @@ impl Loader {
         let handle_clone = handle.clone();
         let processed = storage.processed.clone();
 
-        let name = name.into();
-
         let hot_reload = self.hot_reload;
 
         let cl = move || {
@@ -132,6 +139,7 @@ impl Loader {
                 data,
                 handle,
                 name,
+                path: Some(path),
                 tracker,
             });
         };
@@ -159,6 +167,7 @@ impl Loader {
             data: Ok(FormatValue::data(data)),
             handle: handle.clone(),
             name: "<Data>".into(),
+            path: None,
             tracker,
         });
 
@@ -155,9 +155,6 @@ pub trait Tracker: Send + 'static {
 
 impl Tracker for () {
     fn success(self: Box<Self>) {}
-    fn fail(self: Box<Self>, e: Error) {
-        eprintln!("error: {}", e);
-        e.iter().skip(1).for_each(|e| eprintln!("caused by: {}", e));
-        eprintln!("note: to handle the error, use a `Progress` other than `()`");
-    }
+    // The `AssetStorage` already reports the error, see `AssetStorage::report_errors`.
+    fn fail(self: Box<Self>, _: Error) {}
 }
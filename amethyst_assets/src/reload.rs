@@ -1,10 +1,10 @@
 //! Defines the `Reload` trait.
 
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::Duration;
 
 use amethyst_core as core;
-use amethyst_core::{ECSBundle, Time};
+use amethyst_core::{ECSBundle, RequiredResource, SystemsBuilder, Time};
 use specs::{DispatcherBuilder, Fetch, FetchMut, System, World};
 
 use {Asset, Format, FormatValue, Loader, Result, Source};
@@ -27,13 +27,34 @@ impl<'a, 'b> ECSBundle<'a, 'b> for HotReloadBundle {
     fn build(
         self,
         world: &mut World,
-        dispatcher: DispatcherBuilder<'a, 'b>,
+        builder: DispatcherBuilder<'a, 'b>,
     ) -> core::Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        dispatcher: SystemsBuilder<'a, 'b>,
+    ) -> core::Result<SystemsBuilder<'a, 'b>> {
         world.write_resource::<Loader>().set_hot_reload(true);
         world.add_resource(self.strategy);
 
         Ok(dispatcher.add(HotReloadSystem, "hot_reload", &[]))
     }
+
+    fn name(&self) -> &str {
+        "hot_reload"
+    }
+
+    fn required_resources(&self) -> Vec<RequiredResource> {
+        vec![RequiredResource::new::<Loader>("Loader")]
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        vec!["hot_reload".to_owned()]
+    }
 }
 
 /// An ECS resource which allows to configure hot reloading.
@@ -59,13 +80,16 @@ pub struct HotReloadStrategy {
 
 impl HotReloadStrategy {
     /// Causes hot reloads every `n` seconds.
+    ///
+    /// The interval is measured in real time, so hot reloading keeps working
+    /// while the game is paused or slowed down.
     pub fn every(n: u8) -> Self {
         use std::u64::MAX;
 
         HotReloadStrategy {
             inner: HotReloadStrategyInner::Every {
                 interval: n,
-                last: Instant::now(),
+                last: Duration::from_secs(0),
                 frame_number: MAX,
             },
         }
@@ -101,10 +125,11 @@ impl HotReloadStrategy {
         }
     }
 
-    /// Crate-internal method to check if reload is necessary.
-    /// `reload_counter` is a per-storage value which is only used
-    /// for and by this method.
-    pub(crate) fn needs_reload(&self, current_frame: u64) -> bool {
+    /// Checks if assets should be reloaded in the frame `current_frame`.
+    ///
+    /// This is `true` for one frame after the strategy decided to reload,
+    /// so other things watching files can be reloaded together with the assets.
+    pub fn needs_reload(&self, current_frame: u64) -> bool {
         match self.inner {
             HotReloadStrategyInner::Every { frame_number, .. } => frame_number == current_frame,
             HotReloadStrategyInner::Trigger { frame_number, .. } => frame_number == current_frame,
@@ -122,7 +147,7 @@ impl Default for HotReloadStrategy {
 enum HotReloadStrategyInner {
     Every {
         interval: u8,
-        last: Instant,
+        last: Duration,
         frame_number: u64,
     },
     Trigger { triggered: bool, frame_number: u64 },
@@ -150,9 +175,9 @@ impl<'a> System<'a> for HotReloadSystem {
                 interval,
                 ref mut last,
                 ref mut frame_number,
-            } => if last.elapsed().as_secs() > interval as u64 {
+            } => if time.absolute_real_time() - *last > Duration::from_secs(interval as u64) {
                 *frame_number = time.frame_number() + 1;
-                *last = Instant::now();
+                *last = time.absolute_real_time();
             },
             HotReloadStrategyInner::Never => {}
         }
@@ -0,0 +1,103 @@
+//! Saving asset handles with the `SaveRegistry`.
+
+use std::marker::PhantomData;
+
+use amethyst_core::bundle::{Error as CoreError, Result as CoreResult};
+use amethyst_core::saveload::{LoadContext, SaveContext, SaveEntry};
+use erased_serde;
+use specs::Join;
+
+use {Asset, AssetStorage, Format, Handle, Loader};
+
+/// The source and name an asset was loaded from.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct AssetPath {
+    /// The name of the source, empty for the default directory.
+    pub source: String,
+    /// The name of the asset in the source.
+    pub name: String,
+}
+
+/// A `SaveEntry` for `Handle<A>` components, saving the path of the asset
+/// and loading it again with a fixed format.
+///
+/// Handles of assets which were created from data are skipped.
+///
+/// E.g. `registry.register_entry(HandleEntry::new("Mesh", ObjFormat, ()))` saves
+/// mesh handles and loads them again as OBJ files.
+pub struct HandleEntry<A: Asset, F: Format<A>> {
+    name: String,
+    format: F,
+    options: F::Options,
+    marker: PhantomData<A>,
+}
+
+impl<A, F> HandleEntry<A, F>
+where
+    A: Asset,
+    F: Format<A>,
+{
+    /// Creates a new entry, which is saved under `name` and loads assets
+    /// with `format` and `options`.
+    pub fn new<N: Into<String>>(name: N, format: F, options: F::Options) -> Self {
+        HandleEntry {
+            name: name.into(),
+            format,
+            options,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A, F> SaveEntry for HandleEntry<A, F>
+where
+    A: Asset,
+    F: Format<A> + Clone + Sync,
+    F::Options: Clone + Sync,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn save(&self, ctx: &SaveContext) -> Box<erased_serde::Serialize> {
+        let entities = ctx.world().entities();
+        let handles = ctx.world().read::<Handle<A>>();
+        let storage = ctx.world().read_resource::<AssetStorage<A>>();
+        let data = (&*entities, &handles)
+            .join()
+            .filter_map(|(entity, handle)| {
+                storage
+                    .path(handle)
+                    .map(|path| (ctx.id(entity), path.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        Box::new(data)
+    }
+
+    fn load(
+        &self,
+        ctx: &LoadContext,
+        deserializer: &mut erased_serde::Deserializer,
+    ) -> CoreResult<()> {
+        let data: Vec<(u32, AssetPath)> = erased_serde::deserialize(deserializer)
+            .map_err(|e| CoreError::from(format!("Invalid `{}` data: {}", self.name, e)))?;
+
+        let loader = ctx.world().read_resource::<Loader>();
+        let storage = ctx.world().read_resource::<AssetStorage<A>>();
+        let mut handles = ctx.world().write::<Handle<A>>();
+        for (id, path) in data {
+            let handle = loader.load_from(
+                path.name,
+                self.format.clone(),
+                self.options.clone(),
+                &path.source[..],
+                (),
+                &storage,
+            );
+            handles.insert(ctx.entity(id), handle);
+        }
+
+        Ok(())
+    }
+}
@@ -2,14 +2,16 @@ use std::marker::PhantomData;
 use std::sync::{Arc, Weak};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
-use amethyst_core::Time;
+use amethyst_core::{ErrorEvent, ErrorQueue, Severity, Time};
 use crossbeam::sync::MsQueue;
+use fnv::FnvHashMap;
 use hibitset::BitSet;
 use rayon::ThreadPool;
 use specs::{Component, Fetch, FetchMut, System, UnprotectedStorage, VecStorage};
 
 use asset::{Asset, FormatValue};
-use error::{ErrorKind, Result, ResultExt};
+use save::AssetPath;
+use error::{Error, ErrorKind, Result, ResultExt};
 use progress::Tracker;
 use reload::{HotReloadStrategy, Reload};
 
@@ -32,8 +34,10 @@ impl Allocator {
 pub struct AssetStorage<A: Asset> {
     assets: VecStorage<A>,
     bitset: BitSet,
+    errors: Vec<ErrorEvent>,
     handles: Vec<Handle<A>>,
     handle_alloc: Allocator,
+    paths: FnvHashMap<u32, AssetPath>,
     pub(crate) processed: Arc<MsQueue<Processed<A>>>,
     reloads: Vec<(WeakHandle<A>, Box<Reload<A>>)>,
     unused_handles: MsQueue<Handle<A>>,
@@ -106,6 +110,13 @@ impl<A: Asset> AssetStorage<A> {
         }
     }
 
+    /// Returns the source and name an asset was loaded from.
+    ///
+    /// Returns `None` for assets created from data.
+    pub fn path(&self, handle: &Handle<A>) -> Option<&AssetPath> {
+        self.paths.get(&handle.id())
+    }
+
     /// Process finished asset data and maintain the storage.
     pub fn process<F>(
         &mut self,
@@ -137,6 +148,8 @@ impl<A: Asset> AssetStorage<A> {
             let bitset = &mut self.bitset;
             let handles = &mut self.handles;
             let reloads = &mut self.reloads;
+            let errors = &mut self.errors;
+            let paths = &mut self.paths;
 
             let f = &mut f;
             let (reload_obj, handle) = match processed {
@@ -144,6 +157,7 @@ impl<A: Asset> AssetStorage<A> {
                     data,
                     handle,
                     name,
+                    path,
                     tracker,
                 } => {
                     let (asset, reload_obj) = match data.map(
@@ -157,6 +171,10 @@ impl<A: Asset> AssetStorage<A> {
                             x
                         }
                         Err(e) => {
+                            errors.push(ErrorEvent::new(
+                                Severity::Error,
+                                format!("Failed to load asset: {}", chain_message(&e)),
+                            ));
                             tracker.fail(e);
 
                             continue;
@@ -166,6 +184,9 @@ impl<A: Asset> AssetStorage<A> {
                     let id = handle.id();
                     bitset.add(id);
                     handles.push(handle.clone());
+                    if let Some(path) = path {
+                        paths.insert(id, path);
+                    }
 
                     // NOTE: the loader has to ensure that a handle will be used
                     // together with a `Data` only once.
@@ -188,7 +209,10 @@ impl<A: Asset> AssetStorage<A> {
                     {
                         Ok(x) => x,
                         Err(e) => {
-                            eprintln!("Failed to hot-reload: {}", e);
+                            errors.push(ErrorEvent::new(
+                                Severity::Warning,
+                                format!("Failed to hot-reload: {}", chain_message(&e)),
+                            ));
 
                             reloads.push((handle.downgrade(), old_reload));
 
@@ -222,6 +246,7 @@ impl<A: Asset> AssetStorage<A> {
                 drop_fn(self.assets.remove(id));
             }
             self.bitset.remove(id);
+            self.paths.remove(&id);
 
             // Can't reuse old handle here, because otherwise weak handles would still be valid.
             // TODO: maybe just store u32?
@@ -239,6 +264,23 @@ impl<A: Asset> AssetStorage<A> {
         }
     }
 
+    /// Moves all pending processing errors to `queue`.
+    ///
+    /// If there is no queue, the errors are logged instead.
+    pub fn report_errors(&mut self, queue: Option<&ErrorQueue>) {
+        match queue {
+            Some(queue) => for error in self.errors.drain(..) {
+                queue.push(error);
+            },
+            None => for error in self.errors.drain(..) {
+                match error.severity {
+                    Severity::Warning => warn!("{}", error.message),
+                    Severity::Error | Severity::Fatal => error!("{}", error.message),
+                }
+            },
+        }
+    }
+
     fn hot_reload(&mut self, pool: &ThreadPool) {
         self.reloads.retain(|&(ref handle, _)| !handle.is_dead());
         while let Some(p) = self.reloads
@@ -273,8 +315,10 @@ impl<A: Asset> Default for AssetStorage<A> {
         AssetStorage {
             assets: Default::default(),
             bitset: Default::default(),
+            errors: Default::default(),
             handles: Default::default(),
             handle_alloc: Default::default(),
+            paths: Default::default(),
             processed: Arc::new(MsQueue::new()),
             reloads: Default::default(),
             unused_handles: MsQueue::new(),
@@ -289,6 +333,14 @@ impl<A: Asset> Drop for AssetStorage<A> {
     }
 }
 
+/// Formats an error together with all of its causes.
+fn chain_message(e: &Error) -> String {
+    e.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(": ")
+}
+
 /// A default implementation for an asset processing system
 /// which converts data to assets and maintains the asset storage
 /// for `A`.
@@ -319,9 +371,10 @@ where
         Fetch<'a, Arc<ThreadPool>>,
         Fetch<'a, Time>,
         Option<Fetch<'a, HotReloadStrategy>>,
+        Option<Fetch<'a, ErrorQueue>>,
     );
 
-    fn run(&mut self, (mut storage, pool, time, strategy): Self::SystemData) {
+    fn run(&mut self, (mut storage, pool, time, strategy, errors): Self::SystemData) {
         use std::ops::Deref;
 
         storage.process(
@@ -330,6 +383,7 @@ where
             &**pool,
             strategy.as_ref().map(Deref::deref),
         );
+        storage.report_errors(errors.as_ref().map(Deref::deref));
     }
 }
 
@@ -378,6 +432,7 @@ pub(crate) enum Processed<A: Asset> {
         data: Result<FormatValue<A>>,
         handle: Handle<A>,
         name: String,
+        path: Option<AssetPath>,
         tracker: Box<Tracker>,
     },
     HotReload {
@@ -3,7 +3,7 @@
 use std::marker::PhantomData;
 
 use amethyst_assets::{AssetStorage, Processor};
-use amethyst_core::bundle::{ECSBundle, Result};
+use amethyst_core::bundle::{ECSBundle, Result, SystemsBuilder};
 use shred::ResourceId;
 use specs::{DispatcherBuilder, World};
 
@@ -17,7 +17,7 @@ use systems::DjSystem;
 /// Will only register the `AudioSink` and the `DjSystem` if an audio output is found.
 /// `DjSystem` will be registered with name "dj_system".
 ///
-/// This will also add the asset processor for `Source`.
+/// This will also add the asset processor for `Source` with name "source_processor".
 ///
 /// ## Errors
 ///
@@ -58,8 +58,17 @@ where
     fn build(
         self,
         world: &mut World,
-        mut builder: DispatcherBuilder<'a, 'b>,
+        builder: DispatcherBuilder<'a, 'b>,
     ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        mut builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
         // Remove option here when specs get support for optional fetch in
         // released version
         if !world.res.has_value(ResourceId::new::<Option<Output>>()) {
@@ -72,14 +81,26 @@ where
             .map(|audio_output| AudioSink::new(audio_output));
 
         world.add_resource(AssetStorage::<Source>::new());
+        builder = builder.add(Processor::<Source>::new(), "source_processor", &[]);
 
         if let Some(sink) = sink {
             world.add_resource(sink);
-            builder = builder
-                .add(Processor::<Source>::new(), "source_processor", &[])
-                .add(DjSystem::new(self.picker), "dj_system", self.dep);
+            builder = builder.add(DjSystem::new(self.picker), "dj_system", self.dep);
         }
 
         Ok(builder)
     }
+
+    fn name(&self) -> &str {
+        "audio"
+    }
+
+    fn required_systems(&self) -> Vec<String> {
+        self.dep.iter().map(|dep| dep.to_string()).collect()
+    }
+
+    /// The `DjSystem` is only added if there is an audio output, so it isn't listed.
+    fn provided_systems(&self) -> Vec<String> {
+        vec!["source_processor".to_owned()]
+    }
 }
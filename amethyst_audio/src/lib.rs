@@ -2,6 +2,8 @@
 extern crate amethyst_assets;
 extern crate amethyst_core;
 extern crate cpal;
+#[macro_use]
+extern crate log;
 extern crate rodio;
 extern crate shred;
 extern crate smallvec;
@@ -5,6 +5,8 @@
 #![crate_type = "lib"]
 #![doc(html_logo_url = "http://tinyurl.com/hgsb45k")]
 
+#[macro_use]
+extern crate log;
 extern crate ron;
 extern crate serde;
 
@@ -112,8 +114,13 @@ where
     T: for<'a> Deserialize<'a> + Serialize + Default,
 {
     fn load<P: AsRef<Path>>(path: P) -> Self {
-        Self::load_no_fallback(path.as_ref()).unwrap_or_else(|e| {
-            println!("1: Failed to load config: {}", e);
+        let path = path.as_ref();
+        Self::load_no_fallback(path).unwrap_or_else(|e| {
+            error!(
+                "Failed to load config from {}, using the default: {}",
+                path.display(),
+                e
+            );
 
             Self::default()
         })
@@ -1,8 +1,15 @@
-use specs::{DispatcherBuilder, World};
+use shred::{Fetch, Resource, SystemData};
+use specs::{DispatcherBuilder, System, World};
+
+use frame_profile::Profiled;
 
 error_chain!{}
 
 /// A bundle of ECS components, resources and systems.
+///
+/// Bundles can declare what they need from the bundles added before them, and the
+/// systems they provide. The `Application` checks these requirements when a bundle is
+/// added, and reports an error if they aren't met instead of panicking later on.
 pub trait ECSBundle<'a, 'b> {
     /// Build and add ECS resources, register components, add systems etc to the Application.
     fn build(
@@ -10,4 +17,284 @@ pub trait ECSBundle<'a, 'b> {
         world: &mut World,
         dispatcher: DispatcherBuilder<'a, 'b>,
     ) -> Result<DispatcherBuilder<'a, 'b>>;
+
+    /// Like `build`, but adds the systems to a `SystemsBuilder`, which records their names
+    /// and their timings in the `FrameProfile`. The `Application` builds bundles through it.
+    ///
+    /// The default implementation calls `build` with the wrapped `DispatcherBuilder`, its
+    /// systems aren't profiled and are assumed to be the `provided_systems`. Bundles which
+    /// want their systems profiled implement this method, and forward `build` to it.
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>>
+    where
+        Self: Sized,
+    {
+        let provided = self.provided_systems();
+        let SystemsBuilder {
+            builder,
+            mut systems,
+            thread_locals,
+        } = builder;
+        let builder = self.build(world, builder)?;
+        systems.extend(provided);
+
+        Ok(SystemsBuilder {
+            builder,
+            systems,
+            thread_locals,
+        })
+    }
+
+    /// The name of the bundle, used in error messages.
+    fn name(&self) -> &str {
+        "unnamed bundle"
+    }
+
+    /// Resources which have to be in the `World` before the bundle is built.
+    fn required_resources(&self) -> Vec<RequiredResource> {
+        Vec::new()
+    }
+
+    /// Names of the systems the systems of this bundle depend on.
+    ///
+    /// They are checked against the systems added to the `SystemsBuilder` so far, see
+    /// `build_systems`.
+    fn required_systems(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Names of the systems this bundle always adds, systems which are only added in some
+    /// cases aren't listed. Adding a system which isn't listed is fine, but a warning is
+    /// logged for listed systems which `build_systems` didn't add.
+    fn provided_systems(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Adds systems to a `DispatcherBuilder`, wrapping each of them in `Profiled` so their
+/// timings end up in the `FrameProfile`.
+///
+/// Bundles implementing `ECSBundle::build_systems` add their systems through it like they
+/// would to a `DispatcherBuilder`.
+pub struct SystemsBuilder<'a, 'b> {
+    builder: DispatcherBuilder<'a, 'b>,
+    systems: Vec<String>,
+    thread_locals: usize,
+}
+
+impl<'a, 'b> SystemsBuilder<'a, 'b> {
+    /// Creates a new builder, adding systems to `builder`.
+    pub fn new(builder: DispatcherBuilder<'a, 'b>) -> Self {
+        SystemsBuilder {
+            builder,
+            systems: Vec::new(),
+            thread_locals: 0,
+        }
+    }
+
+    /// Adds a system, see `DispatcherBuilder::add`. Its timings are recorded under `name`.
+    pub fn add<S>(mut self, system: S, name: &str, dep: &[&str]) -> Self
+    where
+        for<'c> S: System<'c> + Send + 'a,
+    {
+        self.builder = self.builder.add(Profiled::new(system, name), name, dep);
+        self.systems.push(name.to_owned());
+        self
+    }
+
+    /// Adds a thread local system, see `DispatcherBuilder::add_thread_local`. Its timings are
+    /// recorded under `thread_local_<n>`, `n` counting the thread local systems.
+    pub fn add_thread_local<S>(mut self, system: S) -> Self
+    where
+        for<'c> S: System<'c> + 'b,
+    {
+        let name = format!("thread_local_{}", self.thread_locals);
+        self.thread_locals += 1;
+        self.builder = self.builder.add_thread_local(Profiled::new(system, name));
+        self
+    }
+
+    /// Adds a barrier, see `DispatcherBuilder::add_barrier`.
+    pub fn add_barrier(mut self) -> Self {
+        self.builder = self.builder.add_barrier();
+        self
+    }
+
+    /// The names of the systems added so far, in order.
+    pub fn systems(&self) -> &[String] {
+        &self.systems
+    }
+
+    /// Returns the `DispatcherBuilder` with all added systems.
+    pub fn into_inner(self) -> DispatcherBuilder<'a, 'b> {
+        self.builder
+    }
+}
+
+impl<'a, 'b> Default for SystemsBuilder<'a, 'b> {
+    fn default() -> Self {
+        SystemsBuilder::new(DispatcherBuilder::new())
+    }
+}
+
+/// A resource required by a bundle, see `ECSBundle::required_resources`.
+#[derive(Clone, Copy)]
+pub struct RequiredResource {
+    name: &'static str,
+    present: fn(&World) -> bool,
+}
+
+impl RequiredResource {
+    /// Requires a resource of type `T`, `name` is used in error messages.
+    pub fn new<T: Resource>(name: &'static str) -> Self {
+        RequiredResource {
+            name,
+            present: present::<T>,
+        }
+    }
+
+    /// The name of the resource.
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Checks if the resource is in `world`.
+    pub fn is_present(&self, world: &World) -> bool {
+        (self.present)(world)
+    }
+}
+
+fn present<T: Resource>(world: &World) -> bool {
+    Option::<Fetch<T>>::fetch(&world.res, 0).is_some()
+}
+
+/// Checks the requirements of `bundle`, given the names of the systems added before it,
+/// see `SystemsBuilder::systems`.
+///
+/// Returns an error naming the bundle and everything it misses.
+pub fn check_requirements<'a, 'b, B>(bundle: &B, world: &World, systems: &[String]) -> Result<()>
+where
+    B: ECSBundle<'a, 'b>,
+{
+    let mut missing = bundle
+        .required_resources()
+        .iter()
+        .filter(|resource| !resource.is_present(world))
+        .map(|resource| format!("resource `{}`", resource.name()))
+        .collect::<Vec<_>>();
+    missing.extend(
+        bundle
+            .required_systems()
+            .into_iter()
+            .filter(|system| !systems.contains(system))
+            .map(|system| format!("system `{}`", system)),
+    );
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Bundle `{}` requires {}, add the bundles providing them first",
+            bundle.name(),
+            missing.join(", ")
+        ).into())
+    }
+}
+
+/// Logs a warning for each system of `provided` which isn't in `added`, the names of the
+/// systems the bundle named `bundle` added in its `build`.
+pub fn check_provided_systems(bundle: &str, provided: &[String], added: &[String]) {
+    for system in provided.iter().filter(|system| !added.contains(system)) {
+        warn!("Bundle `{}` lists the system `{}`, but didn't add it", bundle, system);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Nop;
+
+    impl<'a> System<'a> for Nop {
+        type SystemData = ();
+
+        fn run(&mut self, _: ()) {}
+    }
+
+    struct First;
+
+    impl<'a, 'b> ECSBundle<'a, 'b> for First {
+        fn build(
+            self,
+            world: &mut World,
+            builder: DispatcherBuilder<'a, 'b>,
+        ) -> Result<DispatcherBuilder<'a, 'b>> {
+            self.build_systems(world, SystemsBuilder::new(builder))
+                .map(SystemsBuilder::into_inner)
+        }
+
+        fn build_systems(
+            self,
+            world: &mut World,
+            builder: SystemsBuilder<'a, 'b>,
+        ) -> Result<SystemsBuilder<'a, 'b>> {
+            world.add_resource(0u32);
+            Ok(builder.add(Nop, "first", &[]))
+        }
+
+        fn provided_systems(&self) -> Vec<String> {
+            vec!["first".to_owned()]
+        }
+    }
+
+    struct Second;
+
+    impl<'a, 'b> ECSBundle<'a, 'b> for Second {
+        fn build(
+            self,
+            _: &mut World,
+            builder: DispatcherBuilder<'a, 'b>,
+        ) -> Result<DispatcherBuilder<'a, 'b>> {
+            Ok(builder.add(Nop, "second", &["first"]))
+        }
+
+        fn name(&self) -> &str {
+            "second"
+        }
+
+        fn required_resources(&self) -> Vec<RequiredResource> {
+            vec![RequiredResource::new::<u32>("u32")]
+        }
+
+        fn required_systems(&self) -> Vec<String> {
+            vec!["first".to_owned()]
+        }
+
+        fn provided_systems(&self) -> Vec<String> {
+            vec!["second".to_owned()]
+        }
+    }
+
+    #[test]
+    fn requirements() {
+        let mut world = World::new();
+        let error = check_requirements(&Second, &world, &[]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Bundle `second` requires resource `u32`, system `first`, \
+             add the bundles providing them first"
+        );
+
+        let builder = First
+            .build_systems(&mut world, SystemsBuilder::default())
+            .unwrap();
+        assert!(check_requirements(&Second, &world, builder.systems()).is_ok());
+
+        // Bundles only implementing `build` are assumed to add their provided systems.
+        let builder = Second.build_systems(&mut world, builder).unwrap();
+        assert_eq!(builder.systems(), &["first".to_owned(), "second".to_owned()]);
+    }
 }
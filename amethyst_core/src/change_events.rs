@@ -0,0 +1,564 @@
+//! Events for created and deleted entities, and for changed components.
+//!
+//! The `EntityEventSystem` writes `EntityEvent`s to the `EventChannel<EntityEvent>` resource,
+//! and a `ComponentEventSystem<C>` or `ReadOnlyComponentEventSystem<C>` writes
+//! `ComponentEvent<C>`s to the `EventChannel<ComponentEvent<C>>` resource. They are added by
+//! their bundles.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Deref;
+
+use hibitset::BitSet;
+use shrev::EventChannel;
+use specs::{Component, DispatcherBuilder, Entities, Entity, FetchMut, FlaggedStorage, Join,
+            MaskedStorage, ReadStorage, Storage, System, UnprotectedStorage, World,
+            WriteStorage};
+
+use bundle::{ECSBundle, Result, SystemsBuilder};
+
+/// An entity was created or deleted.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum EntityEvent {
+    /// The entity was created.
+    EntityCreated(Entity),
+    /// The entity was deleted.
+    EntityDeleted(Entity),
+}
+
+/// What happened to a component.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ComponentChange {
+    /// The component was added to the entity.
+    Inserted,
+    /// The component was accessed mutably.
+    Modified,
+    /// The component was removed, or the entity was deleted.
+    Removed,
+}
+
+/// A component of type `C` changed.
+pub struct ComponentEvent<C> {
+    /// The entity owning the component.
+    pub entity: Entity,
+    /// What happened to the component.
+    pub change: ComponentChange,
+    marker: PhantomData<fn() -> C>,
+}
+
+impl<C> ComponentEvent<C> {
+    /// Creates a new event.
+    pub fn new(entity: Entity, change: ComponentChange) -> Self {
+        ComponentEvent {
+            entity,
+            change,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<C> Clone for ComponentEvent<C> {
+    fn clone(&self) -> Self {
+        ComponentEvent::new(self.entity, self.change)
+    }
+}
+
+impl<C> Copy for ComponentEvent<C> {}
+
+impl<C> fmt::Debug for ComponentEvent<C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ComponentEvent")
+            .field("entity", &self.entity)
+            .field("change", &self.change)
+            .finish()
+    }
+}
+
+impl<C> PartialEq for ComponentEvent<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entity == other.entity && self.change == other.change
+    }
+}
+
+/// Writes an `EntityEvent` for every entity created or deleted since its last run.
+///
+/// Entities which are created and deleted between two runs are not reported.
+#[derive(Default)]
+pub struct EntityEventSystem {
+    /// Ids of the entities alive at the last run.
+    alive: BitSet,
+    /// The entities alive at the last run, indexed by their id.
+    entities: Vec<Option<Entity>>,
+}
+
+impl EntityEventSystem {
+    /// Creates a new entity event system.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl<'a> System<'a> for EntityEventSystem {
+    type SystemData = (Entities<'a>, FetchMut<'a, EventChannel<EntityEvent>>);
+
+    fn run(&mut self, (entities, mut events): Self::SystemData) {
+        let alive = (&*entities).open().0;
+        for id in (&self.alive & !alive).join() {
+            if let Some(old) = self.entities[id as usize].take() {
+                events.single_write(EntityEvent::EntityDeleted(old));
+            }
+        }
+
+        self.alive.clear();
+        for entity in (&*entities).join() {
+            let id = entity.id();
+            self.alive.add(id);
+            if self.entities.len() <= id as usize {
+                self.entities.resize(id as usize + 1, None);
+            }
+
+            // A reused id means the old entity was deleted.
+            match mem::replace(&mut self.entities[id as usize], Some(entity)) {
+                Some(old) if old == entity => {}
+                Some(old) => {
+                    events.single_write(EntityEvent::EntityDeleted(old));
+                    events.single_write(EntityEvent::EntityCreated(entity));
+                }
+                None => events.single_write(EntityEvent::EntityCreated(entity)),
+            }
+        }
+    }
+}
+
+/// The entities which had a component at the last run of a component event system.
+#[derive(Default)]
+struct ComponentTracker {
+    /// Ids of the entities which had the component.
+    present: BitSet,
+    /// The entities which had the component, indexed by their id.
+    entities: Vec<Option<Entity>>,
+}
+
+impl ComponentTracker {
+    /// Writes the events for the changes since the last call.
+    fn track<'e, C, D, S>(
+        &mut self,
+        entities: &Entities,
+        components: &Storage<'e, C, S>,
+        events: &mut EventChannel<ComponentEvent<C>>,
+    ) where
+        C: Component<Storage = FlaggedStorage<C, D>>,
+        D: UnprotectedStorage<C>,
+        S: Deref<Target = MaskedStorage<C>>,
+    {
+        use self::ComponentChange::*;
+
+        let (mask, storage) = components.open();
+        for id in (&self.present & !mask).join() {
+            if let Some(old) = self.entities[id as usize].take() {
+                events.single_write(ComponentEvent::new(old, Removed));
+            }
+        }
+
+        self.present.clear();
+        for (entity, _) in (&**entities, mask).join() {
+            let id = entity.id();
+            self.present.add(id);
+            if self.entities.len() <= id as usize {
+                self.entities.resize(id as usize + 1, None);
+            }
+
+            // A reused id means the component of the old entity was removed.
+            match mem::replace(&mut self.entities[id as usize], Some(entity)) {
+                Some(old) if old == entity => if storage.flagged(entity) {
+                    events.single_write(ComponentEvent::new(entity, Modified));
+                },
+                Some(old) => {
+                    events.single_write(ComponentEvent::new(old, Removed));
+                    events.single_write(ComponentEvent::new(entity, Inserted));
+                }
+                None => events.single_write(ComponentEvent::new(entity, Inserted)),
+            }
+        }
+    }
+}
+
+/// Writes a `ComponentEvent<C>` for every component inserted, modified or removed
+/// since its last run.
+///
+/// Modifications are detected with the flags of the `FlaggedStorage` of `C`, which this
+/// system clears after each run.
+///
+/// Components whose flags are consumed by another system, like the `LocalTransform` flags by
+/// the `TransformSystem`, need a `ReadOnlyComponentEventSystem` instead.
+pub struct ComponentEventSystem<C> {
+    tracker: ComponentTracker,
+    marker: PhantomData<fn() -> C>,
+}
+
+impl<C> Default for ComponentEventSystem<C> {
+    fn default() -> Self {
+        ComponentEventSystem {
+            tracker: ComponentTracker::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<C> ComponentEventSystem<C> {
+    /// Creates a new component event system.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl<'a, C, D> System<'a> for ComponentEventSystem<C>
+where
+    C: Component<Storage = FlaggedStorage<C, D>>,
+    D: UnprotectedStorage<C> + Send + Sync + 'static,
+{
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, C>,
+        FetchMut<'a, EventChannel<ComponentEvent<C>>>,
+    );
+
+    fn run(&mut self, (entities, mut components, mut events): Self::SystemData) {
+        self.tracker.track(&entities, &components, &mut events);
+        (&mut components).open().1.clear_flags();
+    }
+}
+
+/// Writes a `ComponentEvent<C>` for every component inserted, modified or removed
+/// since its last run, like the `ComponentEventSystem`, but leaves the flags of `C` to
+/// another system.
+///
+/// It only reads `C`, so it can run in parallel to other systems reading it. It has to run
+/// after the systems modifying `C` but before the one clearing the flags, otherwise
+/// modifications are missed or reported twice.
+pub struct ReadOnlyComponentEventSystem<C> {
+    tracker: ComponentTracker,
+    marker: PhantomData<fn() -> C>,
+}
+
+impl<C> Default for ReadOnlyComponentEventSystem<C> {
+    fn default() -> Self {
+        ReadOnlyComponentEventSystem {
+            tracker: ComponentTracker::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<C> ReadOnlyComponentEventSystem<C> {
+    /// Creates a new read-only component event system.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl<'a, C, D> System<'a> for ReadOnlyComponentEventSystem<C>
+where
+    C: Component<Storage = FlaggedStorage<C, D>>,
+    D: UnprotectedStorage<C> + Send + Sync + 'static,
+{
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, C>,
+        FetchMut<'a, EventChannel<ComponentEvent<C>>>,
+    );
+
+    fn run(&mut self, (entities, components, mut events): Self::SystemData) {
+        self.tracker.track(&entities, &components, &mut events);
+    }
+}
+
+/// Adds the `EventChannel<EntityEvent>` resource, and the `EntityEventSystem`
+/// with name "entity_event_system".
+///
+/// ## Errors
+///
+/// No errors will be returned by this bundle.
+#[derive(Default)]
+pub struct EntityEventsBundle<'a> {
+    dep: &'a [&'a str],
+}
+
+impl<'a> EntityEventsBundle<'a> {
+    /// Create a new entity events bundle
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set dependencies for the `EntityEventSystem`
+    pub fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
+        self.dep = dep;
+        self
+    }
+}
+
+impl<'a, 'b, 'c> ECSBundle<'a, 'b> for EntityEventsBundle<'c> {
+    fn build(
+        self,
+        world: &mut World,
+        builder: DispatcherBuilder<'a, 'b>,
+    ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
+        world.add_resource(EventChannel::<EntityEvent>::new());
+
+        Ok(builder.add(EntityEventSystem::new(), "entity_event_system", self.dep))
+    }
+
+    fn name(&self) -> &str {
+        "entity_events"
+    }
+
+    fn required_systems(&self) -> Vec<String> {
+        self.dep.iter().map(|dep| dep.to_string()).collect()
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        vec!["entity_event_system".to_owned()]
+    }
+}
+
+/// Registers `C`, and adds the `EventChannel<ComponentEvent<C>>` resource and a
+/// `ComponentEventSystem<C>`, or a `ReadOnlyComponentEventSystem<C>` when keeping the flags,
+/// with the given name.
+///
+/// Add the system after the systems modifying `C`, see `ComponentEventSystem`.
+///
+/// ## Errors
+///
+/// No errors will be returned by this bundle.
+pub struct ComponentEventsBundle<'a, C> {
+    name: &'a str,
+    dep: &'a [&'a str],
+    keep_flags: bool,
+    marker: PhantomData<fn() -> C>,
+}
+
+impl<'a, C> ComponentEventsBundle<'a, C> {
+    /// Create a new component events bundle, `name` is the name of the system.
+    pub fn new(name: &'a str) -> Self {
+        ComponentEventsBundle {
+            name,
+            dep: &[],
+            keep_flags: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// Set dependencies for the `ComponentEventSystem`
+    pub fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
+        self.dep = dep;
+        self
+    }
+
+    /// Leave the flags of `C` to another system, see `ReadOnlyComponentEventSystem`.
+    pub fn keeping_flags(mut self) -> Self {
+        self.keep_flags = true;
+        self
+    }
+}
+
+impl<'a, 'b, 'c, C, D> ECSBundle<'a, 'b> for ComponentEventsBundle<'c, C>
+where
+    C: Component<Storage = FlaggedStorage<C, D>>,
+    D: UnprotectedStorage<C> + Send + Sync + 'static,
+{
+    fn build(
+        self,
+        world: &mut World,
+        builder: DispatcherBuilder<'a, 'b>,
+    ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
+        world.register::<C>();
+        world.add_resource(EventChannel::<ComponentEvent<C>>::new());
+
+        Ok(if self.keep_flags {
+            let system = ReadOnlyComponentEventSystem::<C>::new();
+            builder.add(system, self.name, self.dep)
+        } else {
+            builder.add(ComponentEventSystem::<C>::new(), self.name, self.dep)
+        })
+    }
+
+    fn name(&self) -> &str {
+        "component_events"
+    }
+
+    fn required_systems(&self) -> Vec<String> {
+        self.dep.iter().map(|dep| dep.to_string()).collect()
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        vec![self.name.to_owned()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shred::RunNow;
+    use shrev::EventChannel;
+    use specs::{Component, DenseVecStorage, FlaggedStorage, World};
+
+    use super::*;
+    use test_util::read_events as read;
+
+    #[derive(Debug)]
+    struct Score(u32);
+
+    impl Component for Score {
+        type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+    }
+
+    #[test]
+    fn entity_events() {
+        let mut world = World::new();
+        world.add_resource(EventChannel::<EntityEvent>::new());
+        let mut reader = world
+            .write_resource::<EventChannel<EntityEvent>>()
+            .register_reader();
+        let mut system = EntityEventSystem::new();
+
+        let e1 = world.create_entity().build();
+        system.run_now(&world.res);
+        assert_eq!(
+            read::<EntityEvent>(&world, &mut reader),
+            vec![EntityEvent::EntityCreated(e1)]
+        );
+
+        let _ = world.delete_entity(e1);
+        system.run_now(&world.res);
+        assert_eq!(
+            read::<EntityEvent>(&world, &mut reader),
+            vec![EntityEvent::EntityDeleted(e1)]
+        );
+
+        let e2 = world.create_entity().build();
+        system.run_now(&world.res);
+        let _ = world.delete_entity(e2);
+        let e3 = world.create_entity().build();
+        assert_eq!(e3.id(), e2.id());
+        system.run_now(&world.res);
+        assert_eq!(
+            read::<EntityEvent>(&world, &mut reader),
+            vec![
+                EntityEvent::EntityCreated(e2),
+                EntityEvent::EntityDeleted(e2),
+                EntityEvent::EntityCreated(e3),
+            ]
+        );
+    }
+
+    #[test]
+    fn component_events() {
+        use super::ComponentChange::*;
+
+        let mut world = World::new();
+        world.register::<Score>();
+        world.add_resource(EventChannel::<ComponentEvent<Score>>::new());
+        let mut reader = world
+            .write_resource::<EventChannel<ComponentEvent<Score>>>()
+            .register_reader();
+        let mut system = ComponentEventSystem::<Score>::new();
+
+        let e1 = world.create_entity().with(Score(0)).build();
+        system.run_now(&world.res);
+        assert_eq!(
+            read::<ComponentEvent<Score>>(&world, &mut reader),
+            vec![ComponentEvent::new(e1, Inserted)]
+        );
+
+        system.run_now(&world.res);
+        assert!(read::<ComponentEvent<Score>>(&world, &mut reader).is_empty());
+
+        world.write::<Score>().get_mut(e1).unwrap().0 += 1;
+        system.run_now(&world.res);
+        assert_eq!(
+            read::<ComponentEvent<Score>>(&world, &mut reader),
+            vec![ComponentEvent::new(e1, Modified)]
+        );
+
+        world.write::<Score>().remove(e1);
+        system.run_now(&world.res);
+        assert_eq!(
+            read::<ComponentEvent<Score>>(&world, &mut reader),
+            vec![ComponentEvent::new(e1, Removed)]
+        );
+    }
+
+    #[test]
+    fn reused_id() {
+        use super::ComponentChange::*;
+
+        let mut world = World::new();
+        world.register::<Score>();
+        world.add_resource(EventChannel::<ComponentEvent<Score>>::new());
+        let mut reader = world
+            .write_resource::<EventChannel<ComponentEvent<Score>>>()
+            .register_reader();
+        let mut system = ComponentEventSystem::<Score>::new();
+
+        let e1 = world.create_entity().with(Score(0)).build();
+        system.run_now(&world.res);
+        let _ = world.delete_entity(e1);
+        let e2 = world.create_entity().with(Score(0)).build();
+        assert_eq!(e2.id(), e1.id());
+        system.run_now(&world.res);
+        assert_eq!(
+            read::<ComponentEvent<Score>>(&world, &mut reader),
+            vec![
+                ComponentEvent::new(e1, Inserted),
+                ComponentEvent::new(e1, Removed),
+                ComponentEvent::new(e2, Inserted),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeping_flags() {
+        use super::ComponentChange::*;
+
+        let mut world = World::new();
+        world.register::<Score>();
+        world.add_resource(EventChannel::<ComponentEvent<Score>>::new());
+        let mut reader = world
+            .write_resource::<EventChannel<ComponentEvent<Score>>>()
+            .register_reader();
+        let mut system = ReadOnlyComponentEventSystem::<Score>::new();
+
+        let e1 = world.create_entity().with(Score(0)).build();
+        system.run_now(&world.res);
+        world.write::<Score>().get_mut(e1).unwrap().0 += 1;
+        system.run_now(&world.res);
+        system.run_now(&world.res);
+        assert_eq!(
+            read::<ComponentEvent<Score>>(&world, &mut reader),
+            vec![
+                ComponentEvent::new(e1, Inserted),
+                ComponentEvent::new(e1, Modified),
+                ComponentEvent::new(e1, Modified),
+            ]
+        );
+        assert!((&world.read::<Score>()).open().1.flagged(e1));
+    }
+}
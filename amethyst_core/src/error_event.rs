@@ -0,0 +1,90 @@
+//! Error reporting through event channels.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::mem;
+use std::sync::Mutex;
+
+/// How severe a reported error is.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// Something went wrong, but the game can continue without any action.
+    Warning,
+    /// An operation failed, the game can continue but may be missing data.
+    Error,
+    /// The game cannot continue.
+    Fatal,
+}
+
+/// An error reported by the engine or by game systems.
+///
+/// These are written to the `EventChannel<ErrorEvent>` resource or the `ErrorQueue`, and the
+/// `Application` passes them on to the active state with `State::on_error`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorEvent {
+    /// How severe the error is.
+    pub severity: Severity,
+    /// Description of the error, including its causes.
+    pub message: String,
+}
+
+impl ErrorEvent {
+    /// Creates a new error event.
+    pub fn new<M: Into<String>>(severity: Severity, message: M) -> Self {
+        ErrorEvent {
+            severity,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ErrorEvent {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:?}: {}", self.severity, self.message)
+    }
+}
+
+/// Collects `ErrorEvent`s from systems which only have read access to it, so systems
+/// reporting errors don't have to wait for each other like they would for the
+/// `EventChannel<ErrorEvent>`.
+///
+/// The `Application` moves the collected errors to the `EventChannel<ErrorEvent>` once per
+/// frame.
+#[derive(Debug, Default)]
+pub struct ErrorQueue {
+    errors: Mutex<Vec<ErrorEvent>>,
+}
+
+impl ErrorQueue {
+    /// Creates a new, empty queue.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Reports an error.
+    pub fn push(&self, error: ErrorEvent) {
+        self.errors.lock().unwrap().push(error);
+    }
+
+    /// Removes all reported errors and returns them, in the order they were reported.
+    pub fn drain(&self) -> Vec<ErrorEvent> {
+        mem::replace(&mut *self.errors.lock().unwrap(), Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue() {
+        let queue = ErrorQueue::new();
+        queue.push(ErrorEvent::new(Severity::Warning, "first"));
+        queue.push(ErrorEvent::new(Severity::Error, "second"));
+
+        let errors = queue.drain();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].message, "first");
+        assert_eq!(errors[1].severity, Severity::Error);
+        assert!(queue.drain().is_empty());
+    }
+}
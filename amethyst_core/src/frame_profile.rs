@@ -0,0 +1,351 @@
+//! Per-frame timings of application phases and systems.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Result as IoResult, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rayon_core;
+use shred::{Fetch, Resources, RunNow, RunningTime, SystemData};
+use specs::System;
+
+/// What a `ProfileSpan` measured.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SpanKind {
+    /// A phase of the frame, like event handling or dispatch.
+    Phase,
+    /// A single system.
+    System,
+}
+
+impl SpanKind {
+    fn category(&self) -> &'static str {
+        match *self {
+            SpanKind::Phase => "phase",
+            SpanKind::System => "system",
+        }
+    }
+}
+
+/// A timed section of a frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfileSpan {
+    /// The name of the phase or system.
+    pub name: String,
+    /// Whether this is a phase or a system.
+    pub kind: SpanKind,
+    /// When the span started, relative to the creation of the `FrameProfile`.
+    pub start: Duration,
+    /// How long the span took.
+    pub duration: Duration,
+    /// The thread the span ran on, `0` for the main thread and `n + 1` for
+    /// the `n`th thread of the thread pool.
+    pub thread: usize,
+}
+
+/// The timings of a single frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameTimings {
+    /// The number of the frame.
+    pub frame_number: u64,
+    /// When the frame started, relative to the creation of the `FrameProfile`.
+    pub start: Duration,
+    /// How long the frame took.
+    pub duration: Duration,
+    /// The phases and systems of the frame, in the order they finished.
+    pub spans: Vec<ProfileSpan>,
+}
+
+impl FrameTimings {
+    /// Returns the total time spent in phases or systems with the given name.
+    pub fn total(&self, name: &str) -> Duration {
+        self.spans
+            .iter()
+            .filter(|span| span.name == name)
+            .fold(Duration::from_secs(0), |sum, span| sum + span.duration)
+    }
+}
+
+/// A resource collecting the timings of the last frames.
+///
+/// The `Application` times each phase of a frame (`handle_event`, `fixed_update`,
+/// `update`, `dispatch`, `render`, `maintain` and `handle_errors`), and every system
+/// added through the `ApplicationBuilder` is timed as well. Systems of bundles are timed if
+/// the bundle adds them through a `SystemsBuilder`, like the bundles of amethyst do, see
+/// `ECSBundle::build_systems`.
+#[derive(Debug)]
+pub struct FrameProfile {
+    origin: Instant,
+    frame_start: Instant,
+    capacity: usize,
+    enabled: bool,
+    current: Mutex<Vec<ProfileSpan>>,
+    history: VecDeque<FrameTimings>,
+}
+
+impl Default for FrameProfile {
+    fn default() -> Self {
+        FrameProfile::new(120)
+    }
+}
+
+impl FrameProfile {
+    /// Creates a new profile, keeping the timings of the last `capacity` frames.
+    pub fn new(capacity: usize) -> Self {
+        let now = Instant::now();
+        FrameProfile {
+            origin: now,
+            frame_start: now,
+            capacity,
+            enabled: true,
+            current: Mutex::new(Vec::new()),
+            history: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Checks whether timings are collected.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables collecting timings.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Returns the number of frames kept in the history.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the number of frames kept in the history, dropping the oldest ones if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.history.len() > capacity {
+            self.history.pop_front();
+        }
+    }
+
+    /// Marks the start of a new frame.
+    ///
+    /// This is called by the `Application`.
+    pub fn begin_frame(&mut self) {
+        self.frame_start = Instant::now();
+        self.current.get_mut().unwrap().clear();
+    }
+
+    /// Records a span which started at `start` and ends now.
+    ///
+    /// This only needs a shared borrow, so systems running in parallel can record spans.
+    pub fn record(&self, name: &str, kind: SpanKind, start: Instant) {
+        if !self.enabled {
+            return;
+        }
+
+        let span = ProfileSpan {
+            name: name.to_owned(),
+            kind,
+            start: start.duration_since(self.origin),
+            duration: start.elapsed(),
+            thread: rayon_core::current_thread_index().map(|i| i + 1).unwrap_or(0),
+        };
+        self.current.lock().unwrap().push(span);
+    }
+
+    /// Marks the end of the current frame and moves its timings to the history.
+    ///
+    /// This is called by the `Application`.
+    pub fn end_frame(&mut self, frame_number: u64) {
+        let spans = ::std::mem::replace(self.current.get_mut().unwrap(), Vec::new());
+        if !self.enabled || self.capacity == 0 {
+            return;
+        }
+
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(FrameTimings {
+            frame_number,
+            start: self.frame_start.duration_since(self.origin),
+            duration: self.frame_start.elapsed(),
+            spans,
+        });
+    }
+
+    /// Returns the timings of the last finished frame.
+    pub fn last_frame(&self) -> Option<&FrameTimings> {
+        self.history.back()
+    }
+
+    /// Returns the timings of the kept frames, oldest first.
+    pub fn history(&self) -> &VecDeque<FrameTimings> {
+        &self.history
+    }
+
+    /// Returns the average time spent per frame in phases or systems with the given name.
+    pub fn average(&self, name: &str) -> Option<Duration> {
+        if self.history.is_empty() {
+            return None;
+        }
+
+        let total = self.history
+            .iter()
+            .fold(Duration::from_secs(0), |sum, frame| sum + frame.total(name));
+        Some(total / self.history.len() as u32)
+    }
+
+    /// Writes the kept frames in the Chrome trace event format.
+    ///
+    /// The output can be opened with `chrome://tracing`.
+    pub fn write_chrome_trace<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        writer.write_all(b"{\"traceEvents\":[")?;
+        let mut first = true;
+        for frame in &self.history {
+            let frame_name = format!("frame {}", frame.frame_number);
+            let frame_span = (&frame_name[..], "frame", frame.start, frame.duration, 0);
+            let spans = frame
+                .spans
+                .iter()
+                .map(|s| (&s.name[..], s.kind.category(), s.start, s.duration, s.thread));
+            let spans = Some(frame_span).into_iter().chain(spans);
+            for (name, category, start, duration, thread) in spans {
+                if !first {
+                    writer.write_all(b",")?;
+                }
+                first = false;
+                write!(
+                    writer,
+                    "{{\"name\":\"{}\",\"cat\":\"{}\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\
+                     \"pid\":0,\"tid\":{}}}",
+                    escape(name),
+                    category,
+                    micros(start),
+                    micros(duration),
+                    thread,
+                )?;
+            }
+        }
+        writer.write_all(b"]}")?;
+
+        Ok(())
+    }
+
+    /// Saves the kept frames to a file in the Chrome trace event format.
+    pub fn save_chrome_trace<P: AsRef<Path>>(&self, path: P) -> IoResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_chrome_trace(&mut writer)?;
+        writer.flush()
+    }
+}
+
+fn micros(duration: Duration) -> u64 {
+    duration.as_secs() * 1_000_000 + duration.subsec_nanos() as u64 / 1_000
+}
+
+fn escape(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Wraps a system or a local `RunNow` system, recording how long it runs
+/// in the `FrameProfile` resource, if there is one.
+pub struct Profiled<S> {
+    name: String,
+    system: S,
+}
+
+impl<S> Profiled<S> {
+    /// Wraps `system`, recording its timings under `name`.
+    pub fn new<N: Into<String>>(system: S, name: N) -> Self {
+        Profiled {
+            name: name.into(),
+            system,
+        }
+    }
+}
+
+impl<'a, S> System<'a> for Profiled<S>
+where
+    S: System<'a>,
+{
+    type SystemData = (S::SystemData, Option<Fetch<'a, FrameProfile>>);
+
+    fn run(&mut self, (data, profile): Self::SystemData) {
+        let start = Instant::now();
+        self.system.run(data);
+        if let Some(profile) = profile {
+            profile.record(&self.name, SpanKind::System, start);
+        }
+    }
+
+    fn running_time(&self) -> RunningTime {
+        self.system.running_time()
+    }
+}
+
+/// A local `RunNow` system wrapped in `Profiled`.
+///
+/// `RunNow` can't be implemented for `Profiled` directly, because it conflicts
+/// with the implementation for systems.
+pub struct ProfiledLocal<S>(pub Profiled<S>);
+
+impl<'a, S> RunNow<'a> for ProfiledLocal<S>
+where
+    S: RunNow<'a>,
+{
+    fn run_now(&mut self, res: &'a Resources) {
+        let start = Instant::now();
+        self.0.system.run_now(res);
+        if let Some(profile) = Option::<Fetch<FrameProfile>>::fetch(res, 0) {
+            profile.record(&self.0.name, SpanKind::System, start);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolling_history() {
+        let mut profile = FrameProfile::new(2);
+        for frame in 0..3 {
+            profile.begin_frame();
+            profile.record("dispatch", SpanKind::Phase, Instant::now());
+            profile.end_frame(frame);
+        }
+
+        assert_eq!(profile.history().len(), 2);
+        assert_eq!(profile.history()[0].frame_number, 1);
+        assert_eq!(profile.last_frame().unwrap().spans[0].name, "dispatch");
+        assert!(profile.average("dispatch").is_some());
+        assert_eq!(profile.average("missing"), Some(Duration::from_secs(0)));
+    }
+
+    #[test]
+    fn chrome_trace() {
+        let mut profile = FrameProfile::new(4);
+        profile.begin_frame();
+        profile.record("\"quoted\"", SpanKind::System, Instant::now());
+        profile.end_frame(7);
+
+        let mut out = Vec::new();
+        profile.write_chrome_trace(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.starts_with("{\"traceEvents\":[{\"name\":\"frame 7\""));
+        assert!(out.contains("\"name\":\"\\\"quoted\\\"\",\"cat\":\"system\""));
+        assert!(out.ends_with("]}"));
+    }
+}
@@ -3,28 +3,50 @@ pub extern crate cgmath;
 
 #[macro_use]
 extern crate error_chain;
+extern crate erased_serde;
 extern crate fnv;
 extern crate hibitset;
+#[macro_use]
+extern crate log;
 extern crate rayon;
+extern crate rayon_core;
+extern crate ron;
 #[macro_use]
 extern crate serde;
 extern crate shred;
+extern crate shrev;
 extern crate specs;
 
 //#[cfg(test)]
 //extern crate quickcheck;
 
-pub use bundle::{ECSBundle, Error, ErrorKind, Result};
+pub use bundle::{ECSBundle, Error, ErrorKind, RequiredResource, Result, SystemsBuilder};
+pub use change_events::{ComponentChange, ComponentEvent, ComponentEventSystem,
+                        ComponentEventsBundle, EntityEvent, EntityEventSystem, EntityEventsBundle,
+                        ReadOnlyComponentEventSystem};
+pub use error_event::{ErrorEvent, ErrorQueue, Severity};
+pub use frame_profile::{FrameProfile, FrameTimings, ProfileSpan, Profiled, ProfiledLocal, SpanKind};
+pub use thread_pool::{ThreadPoolConfig, ThreadingConfig};
+pub use timers::{Timer, TimerBundle, TimerEvent, TimerSystem, Timers};
 pub use timing::*;
 pub use transform::*;
 
 use std::sync::Arc;
 
 pub mod bundle;
+pub mod change_events;
+pub mod error_event;
 pub mod orientation;
+pub mod saveload;
 pub mod transform;
 pub mod timing;
+pub mod timers;
 pub mod frame_limiter;
+pub mod frame_profile;
+pub mod thread_pool;
+
+#[cfg(test)]
+mod test_util;
 
 /// A rayon thread pool wrapped in an `Arc`. This should be used as resource in `World`.
 pub type ThreadPool = Arc<rayon::ThreadPool>;
@@ -0,0 +1,598 @@
+//! Saving and loading the components of a `World`.
+//!
+//! Components opt in by implementing `SaveComponent` and being registered in the
+//! `SaveRegistry` resource under a unique name. Entity references are written as
+//! ids local to the save file, and remapped to new entities when loading.
+
+use std::cell::RefCell;
+use std::fmt::{Formatter, Result as FmtResult};
+use std::marker::PhantomData;
+use std::result::Result as StdResult;
+
+use erased_serde;
+use fnv::FnvHashMap;
+use ron;
+use serde::{Deserializer, Serialize, Serializer};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeStruct};
+use shred::{Fetch, FetchMut, SystemData};
+use specs::{Component, Entity, Join, World};
+
+use bundle::{Error, Result};
+
+/// The version of the save file layout.
+///
+/// Loading fails for saves written with a newer layout.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A component which can be saved with the `SaveRegistry`.
+pub trait SaveComponent: Component {
+    /// The serialized form of the component.
+    type Data: Serialize + DeserializeOwned;
+
+    /// Converts the component to its serialized form.
+    ///
+    /// Entities have to be stored as the ids returned by `SaveContext::id`.
+    fn save(&self, ctx: &SaveContext) -> Self::Data;
+
+    /// Creates the component from its serialized form.
+    ///
+    /// Saved entity ids are converted back with `LoadContext::entity`, and
+    /// `LoadContext::version` can be used to migrate old saves.
+    fn load(data: Self::Data, ctx: &LoadContext) -> Result<Self>;
+}
+
+/// Saves and loads one kind of data for all entities, usually a component.
+///
+/// `SaveRegistry::register` adds an entry for a `SaveComponent`, other entries can be
+/// added with `SaveRegistry::register_entry`.
+pub trait SaveEntry: Send + Sync + 'static {
+    /// The unique name of the entry in save files.
+    fn name(&self) -> &str;
+
+    /// Collects the data of all entities.
+    fn save(&self, ctx: &SaveContext) -> Box<erased_serde::Serialize>;
+
+    /// Reads the data written by `save` and adds it to the entities.
+    fn load(&self, ctx: &LoadContext, deserializer: &mut erased_serde::Deserializer) -> Result<()>;
+}
+
+/// State of a save in progress.
+pub struct SaveContext<'a> {
+    world: &'a World,
+    ids: RefCell<FnvHashMap<Entity, u32>>,
+}
+
+impl<'a> SaveContext<'a> {
+    fn new(world: &'a World) -> Self {
+        SaveContext {
+            world,
+            ids: RefCell::new(FnvHashMap::default()),
+        }
+    }
+
+    /// Returns the world being saved.
+    pub fn world(&self) -> &World {
+        self.world
+    }
+
+    /// Returns the id of `entity` in the save file.
+    pub fn id(&self, entity: Entity) -> u32 {
+        let mut ids = self.ids.borrow_mut();
+        let next = ids.len() as u32;
+
+        *ids.entry(entity).or_insert(next)
+    }
+}
+
+/// State of a load in progress.
+pub struct LoadContext<'a> {
+    world: &'a World,
+    version: u32,
+    entities: RefCell<FnvHashMap<u32, Entity>>,
+}
+
+impl<'a> LoadContext<'a> {
+    fn new(world: &'a World, version: u32) -> Self {
+        LoadContext {
+            world,
+            version,
+            entities: RefCell::new(FnvHashMap::default()),
+        }
+    }
+
+    /// Returns the world being loaded into.
+    pub fn world(&self) -> &World {
+        self.world
+    }
+
+    /// Returns the version of the save, as set with `SaveRegistry::set_version`
+    /// when it was written.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the entity for an id of the save file, creating it if needed.
+    pub fn entity(&self, id: u32) -> Entity {
+        *self.entities
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(|| self.world.entities().create())
+    }
+
+    fn into_entities(self) -> Vec<Entity> {
+        let mut entities = self.entities.borrow_mut().drain().collect::<Vec<_>>();
+        entities.sort_by_key(|&(id, _)| id);
+
+        entities.into_iter().map(|(_, entity)| entity).collect()
+    }
+}
+
+impl<'a> Drop for LoadContext<'a> {
+    fn drop(&mut self) {
+        // Only a failed load still owns entities, don't leave them half loaded.
+        let entities = self.world.entities();
+        for (_, entity) in self.entities.borrow_mut().drain() {
+            let _ = entities.delete(entity);
+        }
+    }
+}
+
+/// A resource holding the entries which are saved, and the version of the game's save data.
+///
+/// The `TransformBundle` registers `LocalTransform` and `Parent`, the `RenderBundle`
+/// registers `Light`, `Camera` and the mesh and texture handles, the `UiBundle` registers
+/// font handles.
+///
+/// ## Examples
+///
+/// ```
+/// # extern crate amethyst_core;
+/// # extern crate specs;
+/// #
+/// # use amethyst_core::saveload::SaveRegistry;
+/// # use amethyst_core::transform::{LocalTransform, Parent};
+/// # use specs::World;
+/// #
+/// # fn main() {
+/// let mut world = World::new();
+/// world.register::<LocalTransform>();
+/// world.register::<Parent>();
+///
+/// let mut registry = SaveRegistry::new();
+/// registry.register::<LocalTransform>("LocalTransform");
+/// registry.register::<Parent>("Parent");
+///
+/// let parent = world.create_entity().with(LocalTransform::default()).build();
+/// world.create_entity().with(Parent { entity: parent }).build();
+///
+/// let save = registry.save_ron(&world).unwrap();
+///
+/// let mut loaded = World::new();
+/// loaded.register::<LocalTransform>();
+/// loaded.register::<Parent>();
+/// let entities = registry.load_ron(&loaded, &save).unwrap();
+/// assert_eq!(entities.len(), 2);
+/// # }
+/// ```
+#[derive(Default)]
+pub struct SaveRegistry {
+    version: u32,
+    entries: Vec<Box<SaveEntry>>,
+}
+
+impl SaveRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Fetches the registry of `world`, adding an empty one if there is none.
+    pub fn fetch_or_default(world: &mut World) -> FetchMut<SaveRegistry> {
+        let missing = Option::<Fetch<SaveRegistry>>::fetch(&world.res, 0).is_none();
+        if missing {
+            world.add_resource(SaveRegistry::new());
+        }
+
+        world.write_resource()
+    }
+
+    /// Returns the version written to saves.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Sets the version written to saves, defaults to `0`.
+    ///
+    /// Increase it when the save data changes, so components can migrate old saves.
+    /// Saves with a newer version than this fail to load.
+    pub fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    /// Registers a component under `name`, replacing any entry with the same name.
+    pub fn register<C>(&mut self, name: &str)
+    where
+        C: SaveComponent + Send + Sync,
+        C::Data: 'static,
+    {
+        self.register_entry(ComponentEntry::<C> {
+            name: name.to_owned(),
+            marker: PhantomData,
+        });
+    }
+
+    /// Registers a custom entry, replacing any entry with the same name.
+    pub fn register_entry<E: SaveEntry>(&mut self, entry: E) {
+        self.entries.retain(|e| e.name() != entry.name());
+        self.entries.push(Box::new(entry));
+    }
+
+    /// Writes the registered entries of all entities to `serializer`.
+    pub fn save<S: Serializer>(&self, world: &World, serializer: S) -> StdResult<S::Ok, S::Error> {
+        WorldSave {
+            registry: self,
+            ctx: SaveContext::new(world),
+        }.serialize(serializer)
+    }
+
+    /// Reads a save from `deserializer`, creating new entities for the saved ones.
+    ///
+    /// The entities are created atomically, they are merged into the world
+    /// by the next `World::maintain`. If loading fails, the entities created so far
+    /// are deleted again.
+    ///
+    /// Returns the created entities, in the order they were saved.
+    pub fn load<'de, D>(&self, world: &World, deserializer: D) -> StdResult<Vec<Entity>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        WorldLoad {
+            registry: self,
+            world,
+        }.deserialize(deserializer)
+    }
+
+    /// Saves the registered entries of all entities as RON.
+    pub fn save_ron(&self, world: &World) -> Result<String> {
+        let save = WorldSave {
+            registry: self,
+            ctx: SaveContext::new(world),
+        };
+
+        ron::ser::to_string_pretty(&save, Default::default())
+            .map_err(|e| format!("Failed to save world: {}", e).into())
+    }
+
+    /// Loads a save written by `save_ron`.
+    ///
+    /// See `load` for details.
+    pub fn load_ron(&self, world: &World, save: &str) -> Result<Vec<Entity>> {
+        let mut deserializer = ron::de::Deserializer::from_bytes(save.as_bytes());
+        let entities = self.load(world, &mut deserializer)
+            .map_err(|e| format!("Failed to load world: {}", e))?;
+        deserializer
+            .end()
+            .map_err(|e| format!("Failed to load world: {}", e))?;
+
+        Ok(entities)
+    }
+
+    fn entry(&self, name: &str) -> Option<&SaveEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.name() == name)
+            .map(|e| &**e)
+    }
+}
+
+struct ComponentEntry<C> {
+    name: String,
+    marker: PhantomData<C>,
+}
+
+impl<C> SaveEntry for ComponentEntry<C>
+where
+    C: SaveComponent + Send + Sync,
+    C::Data: 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn save(&self, ctx: &SaveContext) -> Box<erased_serde::Serialize> {
+        let entities = ctx.world().entities();
+        let storage = ctx.world().read::<C>();
+        let data = (&*entities, &storage)
+            .join()
+            .map(|(entity, component)| (ctx.id(entity), component.save(ctx)))
+            .collect::<Vec<_>>();
+
+        Box::new(data)
+    }
+
+    fn load(&self, ctx: &LoadContext, deserializer: &mut erased_serde::Deserializer) -> Result<()> {
+        let data: Vec<(u32, C::Data)> = erased_serde::deserialize(deserializer)
+            .map_err(|e| Error::from(format!("Invalid `{}` data: {}", self.name, e)))?;
+        let components = data.into_iter()
+            .map(|(id, data)| C::load(data, ctx).map(|c| (ctx.entity(id), c)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut storage = ctx.world().write::<C>();
+        for (entity, component) in components {
+            storage.insert(entity, component);
+        }
+
+        Ok(())
+    }
+}
+
+struct WorldSave<'a> {
+    registry: &'a SaveRegistry,
+    ctx: SaveContext<'a>,
+}
+
+impl<'a> Serialize for WorldSave<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        let mut save = serializer.serialize_struct("WorldSave", 3)?;
+        save.serialize_field("format", &FORMAT_VERSION)?;
+        save.serialize_field("version", &self.registry.version)?;
+        save.serialize_field("components", &ComponentsSave(self))?;
+
+        save.end()
+    }
+}
+
+struct ComponentsSave<'a: 'b, 'b>(&'b WorldSave<'a>);
+
+impl<'a, 'b> Serialize for ComponentsSave<'a, 'b> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        let entries = &self.0.registry.entries;
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for entry in entries {
+            let data = entry.save(&self.0.ctx);
+            map.serialize_entry(entry.name(), &*data)?;
+        }
+
+        map.end()
+    }
+}
+
+struct WorldLoad<'a> {
+    registry: &'a SaveRegistry,
+    world: &'a World,
+}
+
+impl<'a> WorldLoad<'a> {
+    fn context(&self, format: u32, version: u32) -> StdResult<LoadContext<'a>, String> {
+        if format > FORMAT_VERSION {
+            Err(format!(
+                "Save format {} is newer than the supported format {}",
+                format,
+                FORMAT_VERSION
+            ))
+        } else if version > self.registry.version {
+            Err(format!(
+                "Save version {} is newer than the supported version {}",
+                version,
+                self.registry.version
+            ))
+        } else {
+            Ok(LoadContext::new(self.world, version))
+        }
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for WorldLoad<'a> {
+    type Value = Vec<Entity>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> StdResult<Self::Value, D::Error> {
+        const FIELDS: &[&str] = &["format", "version", "components"];
+
+        deserializer.deserialize_struct("WorldSave", FIELDS, self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for WorldLoad<'a> {
+    type Value = Vec<Entity>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> FmtResult {
+        formatter.write_str("a world save")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> StdResult<Self::Value, A::Error> {
+        let format = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let version = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let ctx = self.context(format, version).map_err(de::Error::custom)?;
+        seq.next_element_seed(ComponentsLoad {
+            registry: self.registry,
+            ctx: &ctx,
+        })?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+
+        Ok(ctx.into_entities())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> StdResult<Self::Value, A::Error> {
+        let mut format = None;
+        let mut version = None;
+        let mut ctx = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match &key[..] {
+                "format" => format = Some(map.next_value()?),
+                "version" => version = Some(map.next_value()?),
+                "components" => {
+                    let loaded = match (format, version) {
+                        (Some(format), Some(version)) => {
+                            self.context(format, version).map_err(de::Error::custom)?
+                        }
+                        _ => {
+                            return Err(de::Error::custom(
+                                "`format` and `version` have to precede `components`",
+                            ))
+                        }
+                    };
+                    map.next_value_seed(ComponentsLoad {
+                        registry: self.registry,
+                        ctx: &loaded,
+                    })?;
+                    ctx = Some(loaded);
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        ctx.map(LoadContext::into_entities)
+            .ok_or_else(|| de::Error::missing_field("components"))
+    }
+}
+
+struct ComponentsLoad<'a: 'b, 'b> {
+    registry: &'b SaveRegistry,
+    ctx: &'b LoadContext<'a>,
+}
+
+impl<'de, 'a, 'b> DeserializeSeed<'de> for ComponentsLoad<'a, 'b> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> StdResult<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a, 'b> Visitor<'de> for ComponentsLoad<'a, 'b> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut Formatter) -> FmtResult {
+        formatter.write_str("a map of saved components")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> StdResult<(), A::Error> {
+        while let Some(name) = map.next_key::<String>()? {
+            match self.registry.entry(&name) {
+                Some(entry) => map.next_value_seed(EntryLoad {
+                    entry,
+                    ctx: self.ctx,
+                })?,
+                None => {
+                    warn!("Skipping `{}` in save, it isn't registered", name);
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct EntryLoad<'a: 'b, 'b> {
+    entry: &'b SaveEntry,
+    ctx: &'b LoadContext<'a>,
+}
+
+impl<'de, 'a, 'b> DeserializeSeed<'de> for EntryLoad<'a, 'b> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> StdResult<(), D::Error> {
+        let mut deserializer = erased_serde::Deserializer::erase(deserializer);
+        self.entry
+            .load(self.ctx, &mut deserializer)
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use transform::{LocalTransform, Parent};
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.register::<LocalTransform>();
+        world.register::<Parent>();
+
+        world
+    }
+
+    fn registry() -> SaveRegistry {
+        let mut registry = SaveRegistry::new();
+        registry.register::<LocalTransform>("LocalTransform");
+        registry.register::<Parent>("Parent");
+
+        registry
+    }
+
+    #[test]
+    fn remaps_entities() {
+        let mut world = world();
+        // Keep the saved entities from starting at index 0.
+        world.create_entity().build();
+        let mut transform = LocalTransform::default();
+        transform.translation.x = 3.0;
+        let root = world.create_entity().with(transform.clone()).build();
+        world.create_entity().with(Parent { entity: root }).build();
+
+        let save = registry().save_ron(&world).unwrap();
+        let mut loaded = self::world();
+        let entities = registry().load_ron(&loaded, &save).unwrap();
+        loaded.maintain();
+
+        assert_eq!(entities.len(), 2);
+        assert_eq!(loaded.read::<LocalTransform>().get(entities[0]), Some(&transform));
+        assert_eq!(
+            loaded.read::<Parent>().get(entities[1]),
+            Some(&Parent {
+                entity: entities[0],
+            })
+        );
+    }
+
+    #[test]
+    fn deletes_entities_of_failed_loads() {
+        struct Failing;
+
+        impl SaveEntry for Failing {
+            fn name(&self) -> &str {
+                "Parent"
+            }
+
+            fn save(&self, _: &SaveContext) -> Box<erased_serde::Serialize> {
+                Box::new(())
+            }
+
+            fn load(&self, _: &LoadContext, _: &mut erased_serde::Deserializer) -> Result<()> {
+                Err("Failing entry".into())
+            }
+        }
+
+        let mut world = world();
+        let root = world
+            .create_entity()
+            .with(LocalTransform::default())
+            .build();
+        world.create_entity().with(Parent { entity: root }).build();
+        let save = registry().save_ron(&world).unwrap();
+
+        let mut registry = registry();
+        registry.register_entry(Failing);
+        let mut loaded = self::world();
+        assert!(registry.load_ron(&loaded, &save).is_err());
+        loaded.maintain();
+
+        assert_eq!((&*loaded.entities()).join().count(), 0);
+        assert_eq!((&loaded.read::<LocalTransform>()).join().count(), 0);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let mut registry = registry();
+        registry.set_version(2);
+        let save = registry.save_ron(&world()).unwrap();
+
+        assert!(self::registry().load_ron(&world(), &save).is_err());
+    }
+}
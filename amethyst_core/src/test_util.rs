@@ -0,0 +1,16 @@
+//! Helpers shared by the tests of this crate.
+
+use shrev::{EventChannel, ReaderId};
+use specs::World;
+
+/// Reads the events of the `EventChannel<E>` resource which `reader` hasn't seen yet.
+pub fn read_events<E>(world: &World, reader: &mut ReaderId) -> Vec<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    world
+        .read_resource::<EventChannel<E>>()
+        .lossy_read(reader)
+        .map(|data| data.cloned().collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,82 @@
+//! Thread pool configuration.
+
+use rayon::Configuration;
+
+/// Configuration of a single rayon thread pool.
+///
+/// All fields are optional, missing values fall back to rayon's defaults.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThreadPoolConfig {
+    /// Number of threads, defaults to the number of logical cores.
+    pub num_threads: Option<usize>,
+    /// Name prefix of the threads, the threads will be named `<prefix>-<index>`.
+    pub thread_name: Option<String>,
+    /// Stack size of the threads in bytes.
+    pub stack_size: Option<usize>,
+}
+
+impl ThreadPoolConfig {
+    /// Creates a config with a fixed number of threads.
+    pub fn with_threads(num_threads: usize) -> Self {
+        ThreadPoolConfig {
+            num_threads: Some(num_threads),
+            ..Default::default()
+        }
+    }
+
+    /// Creates the rayon configuration for this pool.
+    pub fn configuration(&self) -> Configuration {
+        let mut cfg = Configuration::new();
+        if let Some(num_threads) = self.num_threads {
+            cfg = cfg.num_threads(num_threads);
+        }
+        if let Some(ref prefix) = self.thread_name {
+            let prefix = prefix.clone();
+            cfg = cfg.thread_name(move |index| format!("{}-{}", prefix, index));
+        }
+        if let Some(stack_size) = self.stack_size {
+            cfg = cfg.stack_size(stack_size);
+        }
+
+        cfg
+    }
+}
+
+/// Configuration of the thread pools used by an application.
+///
+/// ```ron
+/// (
+///     workers: (num_threads: Some(4), thread_name: Some("worker")),
+///     io: Some((num_threads: Some(2), thread_name: Some("io"))),
+/// )
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThreadingConfig {
+    /// The pool used to dispatch systems and process assets.
+    pub workers: ThreadPoolConfig,
+    /// A separate pool used by the asset `Loader` to read and decode files.
+    ///
+    /// If this is `None`, assets are loaded on the worker pool.
+    pub io: Option<ThreadPoolConfig>,
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::ThreadPool;
+
+    use super::*;
+
+    #[test]
+    fn num_threads() {
+        let config = ThreadPoolConfig {
+            num_threads: Some(2),
+            thread_name: Some("test".to_owned()),
+            stack_size: Some(1024 * 1024),
+        };
+        let pool = ThreadPool::new(config.configuration()).unwrap();
+
+        assert_eq!(pool.current_num_threads(), 2);
+    }
+}
@@ -0,0 +1,527 @@
+//! One-shot and repeating timers following the game time.
+//!
+//! Timers are either `Timer` components of entities, or added to the `Timers` resource.
+//! The `TimerSystem` advances them by `Time::delta_time`, so they stop while the game is
+//! paused and follow the time scale, and writes a `TimerEvent` to the
+//! `EventChannel<TimerEvent>` resource every time one fires.
+//!
+//! Timers of the `Timers` resource belong to the state which was active when they were added,
+//! `Timer` components to the one active when they were created with `Timers::owned`. They are
+//! paused while another state is pushed on top of it, and removed once it stops. Other `Timer`
+//! components run independently of the states.
+
+use std::time::Duration;
+
+use shrev::EventChannel;
+use specs::{Component, DenseVecStorage, DispatcherBuilder, Entities, Entity, Fetch, FetchMut,
+            Join, System, World, WriteStorage};
+
+use bundle::{ECSBundle, Result, SystemsBuilder};
+use timing::Time;
+
+/// Which state a timer belongs to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Owner {
+    /// Assigned to the active state when added to the `Timers`, otherwise like `Global`.
+    Unassigned,
+    /// Runs independently of the states.
+    Global,
+    /// Belongs to the state at `depth` in the state stack, `generation` tells apart the
+    /// states which were at the same depth.
+    State { depth: usize, generation: u64 },
+}
+
+/// A one-shot or repeating timer.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use amethyst_core::timers::Timer;
+///
+/// let spawn_wave = Timer::repeating("spawn_wave", Duration::from_secs(30));
+/// let game_over = Timer::once("game_over", Duration::from_secs(300));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timer {
+    label: String,
+    duration: Duration,
+    elapsed: Duration,
+    repeat: bool,
+    paused: bool,
+    finished: bool,
+    owner: Owner,
+}
+
+impl Timer {
+    /// Creates a timer firing once after `duration`.
+    pub fn once<S: Into<String>>(label: S, duration: Duration) -> Self {
+        Timer {
+            label: label.into(),
+            duration,
+            elapsed: Duration::from_secs(0),
+            repeat: false,
+            paused: false,
+            finished: false,
+            owner: Owner::Unassigned,
+        }
+    }
+
+    /// Creates a timer firing every `duration`.
+    pub fn repeating<S: Into<String>>(label: S, duration: Duration) -> Self {
+        Timer {
+            repeat: true,
+            ..Timer::once(label, duration)
+        }
+    }
+
+    /// Makes the timer run independently of the states.
+    pub fn global(mut self) -> Self {
+        self.owner = Owner::Global;
+        self
+    }
+
+    /// The label written to the `TimerEvent`s of this timer.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The time between starting and firing the timer.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The time elapsed since the timer started or last fired.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The time until the timer fires next.
+    pub fn remaining(&self) -> Duration {
+        if self.finished || self.elapsed >= self.duration {
+            Duration::from_secs(0)
+        } else {
+            self.duration - self.elapsed
+        }
+    }
+
+    /// Checks if the timer fires repeatedly.
+    pub fn is_repeating(&self) -> bool {
+        self.repeat
+    }
+
+    /// Checks if a one-shot timer already fired.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Checks if the timer was paused with `pause`. Timers of inactive states are paused
+    /// too, but this stays `false` for them.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Stops advancing the timer until `resume` is called.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Continues advancing a paused timer.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Starts the timer over, also restarting finished one-shot timers.
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::from_secs(0);
+        self.finished = false;
+    }
+
+    /// Advances the timer by `delta`, returning how often it fired.
+    pub fn tick(&mut self, delta: Duration) -> u32 {
+        if self.paused || self.finished {
+            return 0;
+        }
+
+        self.elapsed += delta;
+        if self.elapsed < self.duration {
+            return 0;
+        }
+
+        if !self.repeat {
+            self.elapsed = self.duration;
+            self.finished = true;
+            return 1;
+        }
+        if self.duration == Duration::from_secs(0) {
+            return 1;
+        }
+
+        let mut fired = 0;
+        while self.elapsed >= self.duration {
+            self.elapsed -= self.duration;
+            fired += 1;
+        }
+        fired
+    }
+}
+
+impl Component for Timer {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Written to the `EventChannel<TimerEvent>` resource when a timer fires.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TimerEvent {
+    /// The entity owning the `Timer` component, or `None` for the timers of the
+    /// `Timers` resource.
+    pub entity: Option<Entity>,
+    /// The label of the timer.
+    pub label: String,
+}
+
+/// A resource holding timers which don't belong to an entity, and tracking the states
+/// owning the timers.
+#[derive(Debug, Default)]
+pub struct Timers {
+    timers: Vec<Timer>,
+    /// The generation of every state on the stack, bottom first.
+    states: Vec<u64>,
+    next_generation: u64,
+}
+
+impl Timers {
+    /// Creates an empty timer resource.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a timer, owned by the active state unless it is `global`.
+    pub fn add(&mut self, timer: Timer) {
+        let timer = self.owned(timer);
+        self.timers.push(timer);
+    }
+
+    /// Makes `timer` belong to the active state unless it is `global`, for use as component.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate amethyst_core;
+    /// # extern crate specs;
+    /// use std::time::Duration;
+    /// use amethyst_core::timers::{Timer, Timers};
+    /// use specs::World;
+    ///
+    /// # fn main() {
+    /// let mut world = World::new();
+    /// world.register::<Timer>();
+    /// world.add_resource(Timers::new());
+    ///
+    /// let timer = world
+    ///     .read_resource::<Timers>()
+    ///     .owned(Timer::once("explode", Duration::from_secs(3)));
+    /// world.create_entity().with(timer).build();
+    /// # }
+    /// ```
+    pub fn owned(&self, mut timer: Timer) -> Timer {
+        if timer.owner == Owner::Unassigned {
+            timer.owner = match self.states.last() {
+                Some(&generation) => Owner::State {
+                    depth: self.states.len() - 1,
+                    generation,
+                },
+                None => Owner::Global,
+            };
+        }
+
+        timer
+    }
+
+    /// Returns the first timer with the given label.
+    pub fn get(&self, label: &str) -> Option<&Timer> {
+        self.timers.iter().find(|timer| timer.label == label)
+    }
+
+    /// Returns the first timer with the given label mutably, to pause or reset it.
+    pub fn get_mut(&mut self, label: &str) -> Option<&mut Timer> {
+        self.timers.iter_mut().find(|timer| timer.label == label)
+    }
+
+    /// Removes all timers with the given label, returning `true` if there were any.
+    pub fn cancel(&mut self, label: &str) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|timer| timer.label != label);
+        self.timers.len() != len
+    }
+
+    /// Iterates over the timers of this resource.
+    pub fn iter(&self) -> ::std::slice::Iter<Timer> {
+        self.timers.iter()
+    }
+
+    /// Called by the `StateMachine` when a state is pushed on the stack.
+    ///
+    /// This should only be called by the engine.  Bad things might happen if you call this in
+    /// your game.
+    pub fn push_state(&mut self) {
+        self.states.push(self.next_generation);
+        self.next_generation += 1;
+    }
+
+    /// Called by the `StateMachine` when the active state is removed from the stack.
+    /// Its timers are removed.
+    ///
+    /// This should only be called by the engine.  Bad things might happen if you call this in
+    /// your game.
+    pub fn pop_state(&mut self) {
+        self.states.pop();
+        let states = &self.states;
+        self.timers.retain(|timer| !is_stale(states, timer.owner));
+    }
+
+    fn is_active(&self, owner: Owner) -> bool {
+        match owner {
+            Owner::State { depth, generation } => {
+                depth + 1 == self.states.len() && self.states[depth] == generation
+            }
+            _ => true,
+        }
+    }
+}
+
+fn is_stale(states: &[u64], owner: Owner) -> bool {
+    match owner {
+        Owner::State { depth, generation } => states.get(depth) != Some(&generation),
+        _ => false,
+    }
+}
+
+/// Advances the timers of the `Timers` resource and the `Timer` components, writes the
+/// `TimerEvent`s, and removes finished one-shot timers and the timers of stopped states.
+#[derive(Default)]
+pub struct TimerSystem {
+    remove: Vec<Entity>,
+}
+
+impl TimerSystem {
+    /// Creates a new timer system.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl<'a> System<'a> for TimerSystem {
+    type SystemData = (
+        Entities<'a>,
+        Fetch<'a, Time>,
+        FetchMut<'a, Timers>,
+        WriteStorage<'a, Timer>,
+        FetchMut<'a, EventChannel<TimerEvent>>,
+    );
+
+    fn run(&mut self, (entities, time, mut timers, mut components, mut events): Self::SystemData) {
+        let delta = time.delta_time();
+
+        let mut owned = ::std::mem::replace(&mut timers.timers, Vec::new());
+        for timer in &mut owned {
+            if timers.is_active(timer.owner) {
+                for _ in 0..timer.tick(delta) {
+                    events.single_write(TimerEvent {
+                        entity: None,
+                        label: timer.label.clone(),
+                    });
+                }
+            }
+        }
+        owned.retain(|timer| !timer.finished);
+        // Timers added while this ran are kept too.
+        owned.append(&mut timers.timers);
+        timers.timers = owned;
+
+        for (entity, timer) in (&*entities, &mut components).join() {
+            if is_stale(&timers.states, timer.owner) {
+                self.remove.push(entity);
+                continue;
+            }
+
+            if timers.is_active(timer.owner) {
+                for _ in 0..timer.tick(delta) {
+                    events.single_write(TimerEvent {
+                        entity: Some(entity),
+                        label: timer.label.clone(),
+                    });
+                }
+            }
+            if timer.finished {
+                self.remove.push(entity);
+            }
+        }
+
+        for entity in self.remove.drain(..) {
+            components.remove(entity);
+        }
+    }
+}
+
+/// Adds the `Timers` and `EventChannel<TimerEvent>` resources, registers `Timer`, and adds
+/// the `TimerSystem` with name "timer_system".
+///
+/// ## Errors
+///
+/// No errors will be returned by this bundle.
+#[derive(Default)]
+pub struct TimerBundle<'a> {
+    dep: &'a [&'a str],
+}
+
+impl<'a> TimerBundle<'a> {
+    /// Create a new timer bundle
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set dependencies for the `TimerSystem`
+    pub fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
+        self.dep = dep;
+        self
+    }
+}
+
+impl<'a, 'b, 'c> ECSBundle<'a, 'b> for TimerBundle<'c> {
+    fn build(
+        self,
+        world: &mut World,
+        builder: DispatcherBuilder<'a, 'b>,
+    ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
+        world.register::<Timer>();
+        world.add_resource(Timers::new());
+        world.add_resource(EventChannel::<TimerEvent>::new());
+
+        Ok(builder.add(TimerSystem::new(), "timer_system", self.dep))
+    }
+
+    fn name(&self) -> &str {
+        "timers"
+    }
+
+    fn required_systems(&self) -> Vec<String> {
+        self.dep.iter().map(|dep| dep.to_string()).collect()
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        vec!["timer_system".to_owned()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use shred::RunNow;
+
+    use super::*;
+    use test_util::read_events as read;
+
+    fn step(world: &mut World, system: &mut TimerSystem, secs: u64) {
+        world
+            .write_resource::<Time>()
+            .set_delta_time(Duration::from_secs(secs));
+        system.run_now(&world.res);
+    }
+
+    #[test]
+    fn tick() {
+        let mut once = Timer::once("once", Duration::from_secs(2));
+        assert_eq!(once.tick(Duration::from_secs(1)), 0);
+        assert_eq!(once.remaining(), Duration::from_secs(1));
+        assert_eq!(once.tick(Duration::from_secs(3)), 1);
+        assert!(once.is_finished());
+        assert_eq!(once.tick(Duration::from_secs(3)), 0);
+
+        let mut repeating = Timer::repeating("repeating", Duration::from_secs(2));
+        assert_eq!(repeating.tick(Duration::from_secs(5)), 2);
+        assert_eq!(repeating.elapsed(), Duration::from_secs(1));
+        repeating.pause();
+        assert_eq!(repeating.tick(Duration::from_secs(5)), 0);
+    }
+
+    #[test]
+    fn timers_follow_states() {
+        let mut world = World::new();
+        world.register::<Timer>();
+        world.add_resource(Time::default());
+        world.add_resource(Timers::new());
+        world.add_resource(EventChannel::<TimerEvent>::new());
+        let mut reader = world
+            .write_resource::<EventChannel<TimerEvent>>()
+            .register_reader();
+        let mut system = TimerSystem::new();
+
+        world.write_resource::<Timers>().push_state();
+        world
+            .write_resource::<Timers>()
+            .add(Timer::repeating("tick", Duration::from_secs(1)));
+        let timer = world
+            .read_resource::<Timers>()
+            .owned(Timer::once("done", Duration::from_secs(2)));
+        let entity = world.create_entity().with(timer).build();
+        let unowned = world
+            .create_entity()
+            .with(Timer::repeating("unowned", Duration::from_secs(5)))
+            .build();
+
+        step(&mut world, &mut system, 1);
+        assert_eq!(
+            read::<TimerEvent>(&world, &mut reader),
+            vec![
+                TimerEvent {
+                    entity: None,
+                    label: "tick".to_owned(),
+                },
+            ]
+        );
+
+        // Pausing the owning state pauses its timers, timer components created without
+        // `Timers::owned` keep running.
+        world.write_resource::<Timers>().push_state();
+        step(&mut world, &mut system, 4);
+        assert_eq!(
+            read::<TimerEvent>(&world, &mut reader),
+            vec![
+                TimerEvent {
+                    entity: Some(unowned),
+                    label: "unowned".to_owned(),
+                },
+            ]
+        );
+
+        world.write_resource::<Timers>().pop_state();
+        step(&mut world, &mut system, 1);
+        assert_eq!(
+            read::<TimerEvent>(&world, &mut reader),
+            vec![
+                TimerEvent {
+                    entity: None,
+                    label: "tick".to_owned(),
+                },
+                TimerEvent {
+                    entity: Some(entity),
+                    label: "done".to_owned(),
+                },
+            ]
+        );
+        assert!(world.read::<Timer>().get(entity).is_none());
+
+        // Stopping the owning state removes its timers.
+        world.write_resource::<Timers>().pop_state();
+        assert!(world.read_resource::<Timers>().get("tick").is_none());
+    }
+}
@@ -5,31 +5,101 @@ use std::time::{Duration, Instant};
 /// Frame timing values.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Time {
-    /// Time elapsed since the last frame in seconds.
+    /// Game time elapsed since the last frame in seconds.
     delta_seconds: f32,
-    /// Time elapsed since the last frame.
+    /// Game time elapsed since the last frame.
     delta_time: Duration,
+    /// Real time elapsed since the last frame in seconds.
+    delta_real_seconds: f32,
+    /// Real time elapsed since the last frame.
+    delta_real_time: Duration,
+    /// Game time elapsed since the start of the session.
+    absolute_time: Duration,
+    /// Real time elapsed since the start of the session.
+    absolute_real_time: Duration,
+    /// Rate at which game time passes compared to real time.
+    time_scale: f32,
+    /// Whether game time is stopped.
+    paused: bool,
     /// Rate at which `State::fixed_update` is called in seconds.
     fixed_seconds: f32,
     /// Rate at which `State::fixed_update` is called.
     fixed_time: Duration,
-    /// Time at which `State::fixed_update` was last called.
-    pub last_fixed_update: Instant,
+    /// Time which has passed but was not yet consumed by fixed updates.
+    fixed_time_accumulator: Duration,
+    /// Maximum number of fixed updates run in a single frame.
+    max_fixed_steps: u32,
+    /// Number of fixed updates run in the current frame.
+    fixed_steps: u32,
+    /// Progress towards the next fixed update, as a fraction of the fixed time step.
+    interpolation_alpha: f32,
     /// The total number of frames that have been played in this session.
     frame_number: u64,
 }
 
 impl Time {
-    /// Gets the time difference between frames in seconds
+    /// Gets the game time difference between frames in seconds.
+    ///
+    /// This is the real time difference scaled by the time scale, and zero while paused.
     pub fn delta_seconds(&self) -> f32 {
         self.delta_seconds
     }
 
-    /// Gets the time difference between frames
+    /// Gets the game time difference between frames.
+    ///
+    /// This is the real time difference scaled by the time scale, and zero while paused.
     pub fn delta_time(&self) -> Duration {
         self.delta_time
     }
 
+    /// Gets the real time difference between frames in seconds, ignoring time scale and pause.
+    pub fn delta_real_seconds(&self) -> f32 {
+        self.delta_real_seconds
+    }
+
+    /// Gets the real time difference between frames, ignoring time scale and pause.
+    pub fn delta_real_time(&self) -> Duration {
+        self.delta_real_time
+    }
+
+    /// Gets the time difference between frames measured by the given clock.
+    pub fn delta_time_of(&self, clock: Clock) -> Duration {
+        match clock {
+            Clock::Game => self.delta_time,
+            Clock::Real => self.delta_real_time,
+        }
+    }
+
+    /// Gets the game time elapsed since the start of the session.
+    pub fn absolute_time(&self) -> Duration {
+        self.absolute_time
+    }
+
+    /// Gets the game time elapsed since the start of the session in seconds.
+    pub fn absolute_time_seconds(&self) -> f64 {
+        duration_to_nanos(self.absolute_time) as f64 / 1.0e9
+    }
+
+    /// Gets the real time elapsed since the start of the session.
+    pub fn absolute_real_time(&self) -> Duration {
+        self.absolute_real_time
+    }
+
+    /// Gets the real time elapsed since the start of the session in seconds.
+    pub fn absolute_real_time_seconds(&self) -> f64 {
+        duration_to_nanos(self.absolute_real_time) as f64 / 1.0e9
+    }
+
+    /// Gets the rate at which game time passes compared to real time.
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Checks whether game time is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
     /// Gets the fixed time step in seconds
     pub fn fixed_seconds(&self) -> f32 {
         self.fixed_seconds
@@ -45,32 +115,69 @@ impl Time {
         self.frame_number
     }
 
-    /// Gets the time at which the last fixed update was called.
-    pub fn last_fixed_update(&self) -> Instant {
-        self.last_fixed_update
+    /// Gets the maximum number of fixed updates run in a single frame.
+    pub fn max_fixed_steps(&self) -> u32 {
+        self.max_fixed_steps
     }
 
-    /// Gets the total number of frames that have been played in this session.
+    /// Gets the number of fixed updates run in the current frame.
+    pub fn fixed_steps(&self) -> u32 {
+        self.fixed_steps
+    }
 
-    /// Sets both `delta_seconds` and `delta_time` based on the seconds given.
+    /// Gets the interpolation factor between the previous and the current fixed update.
+    ///
+    /// This is the time accumulated since the last fixed update as a fraction of the fixed time
+    /// step, in the range `[0, 1)`. Rendering can use it to blend between the last two fixed
+    /// states.
+    pub fn interpolation_alpha(&self) -> f32 {
+        self.interpolation_alpha
+    }
+
+    /// Sets the real time elapsed since the last frame based on the seconds given.
+    ///
+    /// The game time delta is derived from it using the time scale. The absolute clocks only
+    /// advance with `advance_absolute_time`.
     ///
     /// This should only be called by the engine.  Bad things might happen if you call this in
     /// your game.
     pub fn set_delta_seconds(&mut self, secs: f32) {
-        self.delta_seconds = secs;
-        self.delta_time = secs_to_duration(secs);
+        self.set_delta_time(secs_to_duration(secs));
     }
 
-    /// Sets both `delta_time` and `delta_seconds` based on the duration given.
+    /// Sets the real time elapsed since the last frame based on the duration given.
+    ///
+    /// The game time delta is derived from it using the time scale. The absolute clocks only
+    /// advance with `advance_absolute_time`.
     ///
     /// This should only be called by the engine.  Bad things might happen if you call this in
     /// your game.
     pub fn set_delta_time(&mut self, time: Duration) {
-        self.delta_seconds = duration_to_secs(time);
-        self.delta_time = time;
+        let scale = if self.paused { 0.0 } else { self.time_scale };
+        let scaled = nanos_to_duration((duration_to_nanos(time) as f64 * scale as f64) as u64);
+
+        self.delta_real_seconds = duration_to_secs(time);
+        self.delta_real_time = time;
+        self.delta_seconds = duration_to_secs(scaled);
+        self.delta_time = scaled;
     }
 
+    /// Sets the rate at which game time passes compared to real time.
+    ///
+    /// A scale of `0.5` runs the game in slow motion at half speed, `2.0` runs it twice as fast.
+    /// Takes effect from the next frame on. Negative values are treated as zero.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale.max(0.0);
+    }
 
+    /// Pauses or resumes game time.
+    ///
+    /// While paused, the game time delta is zero and fixed updates stop, but the real time delta
+    /// keeps ticking for systems following the real clock, such as UI. The time scale is kept
+    /// and applies again once resumed.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
 
     /// Sets both `fixed_seconds` and `fixed_time` based on the seconds given.
     pub fn set_fixed_seconds(&mut self, secs: f32) {
@@ -84,6 +191,15 @@ impl Time {
         self.fixed_time = time;
     }
 
+    /// Sets the maximum number of fixed updates run in a single frame.
+    ///
+    /// When a frame takes longer than `max_steps` fixed time steps, the remaining time is
+    /// dropped instead of being caught up in later frames, so the simulation slows down rather
+    /// than falling further and further behind.
+    pub fn set_max_fixed_steps(&mut self, max_steps: u32) {
+        self.max_fixed_steps = max_steps;
+    }
+
     /// Increments the current frame number by 1.
     ///
     /// This should only be called by the engine.  Bad things might happen if you call this in
@@ -92,12 +208,55 @@ impl Time {
         self.frame_number += 1;
     }
 
-    /// Indicates a fixed update just finished.
+    /// Adds the game and real time deltas of the current frame to the absolute clocks.
+    ///
+    /// This should only be called by the engine, once per frame.  Bad things might happen if
+    /// you call this in your game.
+    pub fn advance_absolute_time(&mut self) {
+        self.absolute_real_time += self.delta_real_time;
+        self.absolute_time += self.delta_time;
+    }
+
+    /// Adds the time elapsed since the last frame to the fixed update accumulator.
     ///
     /// This should only be called by the engine.  Bad things might happen if you call this in
     /// your game.
-    pub fn finish_fixed_update(&mut self) {
-        self.last_fixed_update += self.fixed_time
+    pub fn accumulate_fixed_time(&mut self) {
+        self.fixed_time_accumulator += self.delta_time;
+        self.fixed_steps = 0;
+    }
+
+    /// Consumes one fixed time step from the accumulator, returning `true` if a fixed update
+    /// should run.
+    ///
+    /// Returns `false` once the accumulator holds less than a full step, or when
+    /// `max_fixed_steps` updates already ran this frame, in which case the remaining backlog is
+    /// dropped. The interpolation alpha is updated whenever this returns `false`.
+    ///
+    /// This should only be called by the engine.  Bad things might happen if you call this in
+    /// your game.
+    pub fn step_fixed_update(&mut self) -> bool {
+        let fixed_nanos = duration_to_nanos(self.fixed_time);
+        if fixed_nanos == 0 {
+            self.fixed_time_accumulator = Duration::from_secs(0);
+            self.interpolation_alpha = 0.0;
+            return false;
+        }
+
+        if self.fixed_time_accumulator >= self.fixed_time {
+            if self.fixed_steps < self.max_fixed_steps {
+                self.fixed_time_accumulator -= self.fixed_time;
+                self.fixed_steps += 1;
+                return true;
+            }
+
+            let backlog = duration_to_nanos(self.fixed_time_accumulator);
+            self.fixed_time_accumulator = nanos_to_duration(backlog % fixed_nanos);
+        }
+
+        self.interpolation_alpha =
+            duration_to_nanos(self.fixed_time_accumulator) as f32 / fixed_nanos as f32;
+        false
     }
 }
 
@@ -106,14 +265,38 @@ impl Default for Time {
         Time {
             delta_seconds: 0.0,
             delta_time: Duration::from_secs(0),
+            delta_real_seconds: 0.0,
+            delta_real_time: Duration::from_secs(0),
+            absolute_time: Duration::from_secs(0),
+            absolute_real_time: Duration::from_secs(0),
+            time_scale: 1.0,
+            paused: false,
             fixed_seconds: duration_to_secs(Duration::new(0, 16666666)),
             fixed_time: Duration::new(0, 16666666),
-            last_fixed_update: Instant::now(),
+            fixed_time_accumulator: Duration::from_secs(0),
+            max_fixed_steps: 5,
+            fixed_steps: 0,
+            interpolation_alpha: 0.0,
             frame_number: 0,
         }
     }
 }
 
+/// Selects which clock a time dependent system follows.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub enum Clock {
+    /// Game time, which follows the time scale and stops while paused.
+    Game,
+    /// Real time, which keeps running at its own pace while the game is paused.
+    Real,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock::Game
+    }
+}
+
 /// A stopwatch which accurately measures elapsed time.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Stopwatch {
@@ -185,7 +368,7 @@ impl Stopwatch {
 // Unit tests
 #[cfg(test)]
 mod tests {
-    use super::Stopwatch;
+    use super::{Clock, Stopwatch, Time};
     use std::thread;
     use std::time::Duration;
 
@@ -281,6 +464,92 @@ mod tests {
             elapsed
         );
     }
+
+    #[test]
+    fn fixed_steps_catch_up() {
+        let mut time = Time::default();
+        time.set_fixed_time(Duration::from_millis(10));
+        time.set_delta_time(Duration::from_millis(35));
+
+        time.accumulate_fixed_time();
+        let mut steps = 0;
+        while time.step_fixed_update() {
+            steps += 1;
+        }
+
+        assert_eq!(3, steps);
+        assert!((time.interpolation_alpha() - 0.5).abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn fixed_steps_capped() {
+        let mut time = Time::default();
+        time.set_fixed_time(Duration::from_millis(10));
+        time.set_max_fixed_steps(2);
+        time.set_delta_time(Duration::from_millis(54));
+
+        time.accumulate_fixed_time();
+        let mut steps = 0;
+        while time.step_fixed_update() {
+            steps += 1;
+        }
+        assert_eq!(2, steps);
+        assert!((time.interpolation_alpha() - 0.4).abs() < 1.0e-4);
+
+        // The backlog was dropped, so the next frame starts from the remainder only.
+        time.set_delta_time(Duration::from_millis(6));
+        time.accumulate_fixed_time();
+        assert!(time.step_fixed_update());
+        assert!(!time.step_fixed_update());
+    }
+
+    #[test]
+    fn time_scale() {
+        let mut time = Time::default();
+        time.set_time_scale(0.5);
+        time.set_delta_time(Duration::from_millis(20));
+
+        assert_eq!(Duration::from_millis(10), time.delta_time());
+        assert_eq!(Duration::from_millis(20), time.delta_real_time());
+        assert_eq!(Duration::from_millis(10), time.delta_time_of(Clock::Game));
+        assert_eq!(Duration::from_millis(20), time.delta_time_of(Clock::Real));
+
+        time.advance_absolute_time();
+        time.set_delta_time(Duration::from_millis(20));
+        time.advance_absolute_time();
+        assert_eq!(Duration::from_millis(20), time.absolute_time());
+        assert_eq!(Duration::from_millis(40), time.absolute_real_time());
+    }
+
+    #[test]
+    fn absolute_time() {
+        let mut time = Time::default();
+        time.set_delta_time(Duration::from_millis(20));
+        time.set_delta_time(Duration::from_millis(30));
+        assert_eq!(Duration::from_secs(0), time.absolute_real_time());
+
+        time.advance_absolute_time();
+        assert_eq!(Duration::from_millis(30), time.absolute_time());
+        assert_eq!(Duration::from_millis(30), time.absolute_real_time());
+    }
+
+    #[test]
+    fn paused() {
+        let mut time = Time::default();
+        time.set_time_scale(2.0);
+        time.set_paused(true);
+        time.set_delta_time(Duration::from_millis(20));
+        time.advance_absolute_time();
+
+        assert_eq!(Duration::from_secs(0), time.delta_time());
+        assert_eq!(Duration::from_millis(20), time.delta_real_time());
+        assert_eq!(Duration::from_secs(0), time.absolute_time());
+
+        time.set_paused(false);
+        time.set_delta_time(Duration::from_millis(20));
+        assert_eq!(Duration::from_millis(40), time.delta_time());
+        assert_eq!(2.0, time.time_scale());
+    }
 }
 
 /// Converts a Duration to the time in seconds.
@@ -2,13 +2,23 @@
 
 use specs::{DispatcherBuilder, World};
 
-use bundle::{ECSBundle, Result};
+use bundle::{ECSBundle, Result, SystemsBuilder};
+use saveload::SaveRegistry;
 use transform::*;
 
 /// Transform bundle
 ///
-/// Will register transform components, and the `TransformSystem`.
-/// `TransformSystem` will be registered with name "transform_system".
+/// Will register transform components, including the `SetWorldTransform` and `Reparent`
+/// requests, `InterpolatedTransform` and the constraint components, the `ParentHierarchy`
+/// resource, the `TransformSystem` and the `ConstraintSystem`.
+/// `LocalTransform`, `Transform2D` and `Parent` are added to the `SaveRegistry`.
+/// `TransformSystem` will be registered with name "transform_system", and `ConstraintSystem`
+/// with name "constraint_system", depending on it.
+///
+/// With `with_interpolation`, the `TransformSnapshotSystem` and the
+/// `TransformInterpolationSystem` are added as well, with names "transform_snapshot_system" and
+/// "transform_interpolation_system". They run between the `TransformSystem` and the
+/// `ConstraintSystem`, so constraints see the interpolated transforms.
 ///
 /// ## Errors
 ///
@@ -21,6 +31,8 @@ use transform::*;
 #[derive(Default)]
 pub struct TransformBundle<'a> {
     dep: &'a [&'a str],
+    orphan_policy: OrphanPolicy,
+    interpolation: bool,
 }
 
 impl<'a> TransformBundle<'a> {
@@ -34,6 +46,19 @@ impl<'a> TransformBundle<'a> {
         self.dep = dep;
         self
     }
+
+    /// Set what happens to the children of deleted entities, defaults to `OrphanPolicy::Delete`
+    pub fn with_orphan_policy(mut self, orphan_policy: OrphanPolicy) -> Self {
+        self.orphan_policy = orphan_policy;
+        self
+    }
+
+    /// Add the systems interpolating the transforms of entities with an
+    /// `InterpolatedTransform` between fixed updates.
+    pub fn with_interpolation(mut self) -> Self {
+        self.interpolation = true;
+        self
+    }
 }
 
 impl<'a, 'b, 'c> ECSBundle<'a, 'b> for TransformBundle<'c> {
@@ -42,14 +67,72 @@ impl<'a, 'b, 'c> ECSBundle<'a, 'b> for TransformBundle<'c> {
         world: &mut World,
         builder: DispatcherBuilder<'a, 'b>,
     ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
         world.register::<Parent>();
         world.register::<LocalTransform>();
         world.register::<Transform>();
+        world.register::<Transform2D>();
+        world.register::<SetWorldTransform>();
+        world.register::<Reparent>();
+        world.register::<InterpolatedTransform>();
+        world.register::<LookAt>();
+        world.register::<Follow>();
+        world.register::<CopyPosition>();
+        world.register::<CopyRotation>();
+        world.register::<DistanceLimit>();
+        world.add_resource(ParentHierarchy::with_orphan_policy(self.orphan_policy));
+
+        {
+            let mut registry = SaveRegistry::fetch_or_default(world);
+            registry.register::<LocalTransform>("LocalTransform");
+            registry.register::<Transform2D>("Transform2D");
+            registry.register::<Parent>("Parent");
+        }
+
+        let mut builder = builder.add(TransformSystem::new(), "transform_system", self.dep);
+        let mut last = "transform_system";
+        if self.interpolation {
+            builder = builder
+                .add(
+                    TransformSnapshotSystem::new(),
+                    "transform_snapshot_system",
+                    &["transform_system"],
+                )
+                .add(
+                    TransformInterpolationSystem::new(),
+                    "transform_interpolation_system",
+                    &["transform_snapshot_system"],
+                );
+            last = "transform_interpolation_system";
+        }
+
+        Ok(builder.add(ConstraintSystem::new(), "constraint_system", &[last]))
+    }
+
+    fn name(&self) -> &str {
+        "transform"
+    }
+
+    fn required_systems(&self) -> Vec<String> {
+        self.dep.iter().map(|dep| dep.to_string()).collect()
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        let mut systems = vec!["transform_system".to_owned()];
+        if self.interpolation {
+            systems.push("transform_snapshot_system".to_owned());
+            systems.push("transform_interpolation_system".to_owned());
+        }
+        systems.push("constraint_system".to_owned());
 
-        Ok(builder.add(
-            TransformSystem::new(),
-            "transform_system",
-            self.dep,
-        ))
+        systems
     }
 }
@@ -2,13 +2,15 @@
 
 use cgmath::{Array, Deg, ElementWise,EuclideanSpace, InnerSpace, Matrix3, Matrix4, One, Point3, Quaternion, Rotation,
              Rotation3, SquareMatrix, Transform,Vector3, Zero};
+use bundle::Result;
 use orientation::Orientation;
+use saveload::{LoadContext, SaveComponent, SaveContext};
 use specs::{Component, DenseVecStorage, FlaggedStorage};
 
 /// Local position, rotation, and scale (from parent if it exists).
 ///
 /// Used for rendering position and orientation.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct LocalTransform {
     /// Quaternion [w (scalar), x, y, z]
     pub rotation: Quaternion<f32>,
@@ -180,6 +182,18 @@ impl Component for LocalTransform {
     type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
 }
 
+impl SaveComponent for LocalTransform {
+    type Data = LocalTransform;
+
+    fn save(&self, _: &SaveContext) -> Self::Data {
+        self.clone()
+    }
+
+    fn load(data: Self::Data, _: &LoadContext) -> Result<Self> {
+        Ok(data)
+    }
+}
+
 impl Transform<Point3<f32>> for LocalTransform {
     fn one() -> Self {
         Default::default()
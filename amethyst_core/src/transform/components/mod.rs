@@ -3,7 +3,11 @@
 pub use self::local_transform::LocalTransform;
 pub use self::parent::Parent;
 pub use self::transform::Transform;
+pub use self::transform_2d::Transform2D;
+pub use self::world_pose::{Reparent, SetWorldTransform};
 
 mod parent;
 mod local_transform;
 mod transform;
+mod transform_2d;
+mod world_pose;
@@ -1,4 +1,6 @@
 
+use bundle::Result;
+use saveload::{LoadContext, SaveComponent, SaveContext};
 use specs::{Component, DenseVecStorage, Entity, FlaggedStorage};
 
 /// Component for defining a parent entity.
@@ -13,3 +15,17 @@ pub struct Parent {
 impl Component for Parent {
     type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
 }
+
+impl SaveComponent for Parent {
+    type Data = u32;
+
+    fn save(&self, ctx: &SaveContext) -> u32 {
+        ctx.id(self.entity)
+    }
+
+    fn load(id: u32, ctx: &LoadContext) -> Result<Self> {
+        Ok(Parent {
+            entity: ctx.entity(id),
+        })
+    }
+}
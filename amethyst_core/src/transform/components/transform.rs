@@ -2,9 +2,11 @@
 
 use std::borrow::Borrow;
 
-use cgmath::{Matrix4, One};
+use cgmath::{InnerSpace, Matrix3, Matrix4, One, Quaternion, Vector3};
 use specs::{Component, DenseVecStorage, FlaggedStorage};
 
+use transform::LocalTransform;
+
 /// Performs a global transformation on the entity (transform from origin).
 ///
 /// Used for rendering position and orientation.
@@ -28,6 +30,52 @@ impl Transform {
 
         true
     }
+
+    /// Returns the world translation.
+    pub fn translation(&self) -> Vector3<f32> {
+        self.0.w.truncate()
+    }
+
+    /// Returns the world rotation.
+    ///
+    /// Only meaningful if the matrix has no shear, which is the case unless a
+    /// parent with non-uniform scale rotates its children.
+    pub fn rotation(&self) -> Quaternion<f32> {
+        self.decompose().rotation
+    }
+
+    /// Returns the world scale.
+    pub fn scale(&self) -> Vector3<f32> {
+        Vector3::new(
+            self.0.x.truncate().magnitude(),
+            self.0.y.truncate().magnitude(),
+            self.0.z.truncate().magnitude(),
+        )
+    }
+
+    /// Splits the matrix into the world translation, rotation and scale.
+    ///
+    /// The result can be used as `LocalTransform` of an entity without parent, or passed
+    /// to `SetWorldTransform` to keep this pose under another parent.
+    pub fn decompose(&self) -> LocalTransform {
+        let scale = self.scale();
+        let axis = |axis: Vector3<f32>, scale: f32| if scale == 0.0 {
+            axis
+        } else {
+            axis / scale
+        };
+        let rotation = Matrix3::from_cols(
+            axis(self.0.x.truncate(), scale.x),
+            axis(self.0.y.truncate(), scale.y),
+            axis(self.0.z.truncate(), scale.z),
+        );
+
+        LocalTransform {
+            rotation: Quaternion::from(rotation),
+            scale,
+            translation: self.translation(),
+        }
+    }
 }
 
 impl Component for Transform {
@@ -0,0 +1,103 @@
+//! Local 2D transform component.
+
+use cgmath::{Angle, Array, Matrix4, Quaternion, Rad, Rotation3, Vector2, Vector3, Zero};
+use bundle::Result;
+use saveload::{LoadContext, SaveComponent, SaveContext};
+use specs::{Component, DenseVecStorage, FlaggedStorage};
+use transform::LocalTransform;
+
+/// Local position, rotation, and scale on the xy plane (from parent if it exists).
+///
+/// An alternative to `LocalTransform` for 2D games. The `TransformSystem` sets the
+/// `LocalTransform` of the entity from it whenever it changes, inserting one if needed, so it
+/// works with `Parent` hierarchies like `LocalTransform` does. Don't modify the
+/// `LocalTransform` of entities with a `Transform2D`, the changes are overwritten.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Transform2D {
+    /// Position on the xy plane
+    pub position: Vector2<f32>,
+    /// Counterclockwise rotation around the z axis
+    pub rotation: Rad<f32>,
+    /// Scale along the x and y axes
+    pub scale: Vector2<f32>,
+    /// The z coordinate, entities with a higher layer are drawn in front
+    /// with the default cameras
+    pub layer: f32,
+}
+
+impl Transform2D {
+    /// Create a new `Transform2D` at the origin.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the position.
+    pub fn set_position(&mut self, position: Vector2<f32>) -> &mut Self {
+        self.position = position;
+        self
+    }
+
+    /// Move relatively to its current position, independently from its rotation.
+    pub fn move_global(&mut self, direction: Vector2<f32>) -> &mut Self {
+        self.position += direction;
+        self
+    }
+
+    /// Move relatively to its current position and rotation.
+    pub fn move_local(&mut self, direction: Vector2<f32>) -> &mut Self {
+        let (sin, cos) = self.rotation.sin_cos();
+        self.position += Vector2::new(
+            direction.x * cos - direction.y * sin,
+            direction.x * sin + direction.y * cos,
+        );
+        self
+    }
+
+    /// Add an angle to the current rotation.
+    pub fn rotate<A: Into<Rad<f32>>>(&mut self, angle: A) -> &mut Self {
+        self.rotation = (self.rotation + angle.into()).normalize();
+        self
+    }
+
+    /// Returns the local object matrix for the transform.
+    pub fn matrix(&self) -> Matrix4<f32> {
+        LocalTransform::from(self.clone()).matrix()
+    }
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Transform2D {
+            position: Vector2::zero(),
+            rotation: Rad::zero(),
+            scale: Vector2::from_value(1.),
+            layer: 0.,
+        }
+    }
+}
+
+impl From<Transform2D> for LocalTransform {
+    fn from(transform: Transform2D) -> Self {
+        LocalTransform {
+            rotation: Quaternion::from_angle_z(transform.rotation),
+            scale: Vector3::new(transform.scale.x, transform.scale.y, 1.),
+            translation: transform.position.extend(transform.layer),
+        }
+    }
+}
+
+impl Component for Transform2D {
+    type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+}
+
+impl SaveComponent for Transform2D {
+    type Data = Transform2D;
+
+    fn save(&self, _: &SaveContext) -> Self::Data {
+        self.clone()
+    }
+
+    fn load(data: Self::Data, _: &LoadContext) -> Result<Self> {
+        Ok(data)
+    }
+}
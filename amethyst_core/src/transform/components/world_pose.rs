@@ -0,0 +1,39 @@
+//! Requests to place entities in world space.
+
+use specs::{Component, Entity, HashMapStorage};
+
+use transform::LocalTransform;
+
+/// Places the entity at a world space pose.
+///
+/// The `TransformSystem` sets the `LocalTransform` of the entity so that, combined with
+/// the transforms of its parents, it ends up at the given translation, rotation and scale.
+/// The component is removed afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use amethyst_core::cgmath::Vector3;
+/// use amethyst_core::transform::{LocalTransform, SetWorldTransform};
+///
+/// let mut pose = LocalTransform::default();
+/// pose.translation = Vector3::new(1.0, 0.0, 5.0);
+/// let request = SetWorldTransform(pose);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetWorldTransform(pub LocalTransform);
+
+impl Component for SetWorldTransform {
+    type Storage = HashMapStorage<Self>;
+}
+
+/// Moves the entity to another parent, or to no parent with `None`, keeping its world pose.
+///
+/// The `TransformSystem` replaces the `Parent` of the entity and sets its `LocalTransform`
+/// relative to the new parent. The component is removed afterwards.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reparent(pub Option<Entity>);
+
+impl Component for Reparent {
+    type Storage = HashMapStorage<Self>;
+}
@@ -0,0 +1,498 @@
+//! Components constraining the world transform of an entity to another entity.
+//!
+//! The constraints are applied by the `ConstraintSystem` after the `TransformSystem` ran,
+//! using the world transforms of the targets from that run. The constrained entities get
+//! a new `LocalTransform` and `Transform`, and the `Transform`s of their children are
+//! updated to match.
+//!
+//! An entity can have several constraints. The position constraints are applied first, in the
+//! order `CopyPosition`, `Follow`, `DistanceLimit`, then the rotation constraints `CopyRotation`
+//! and `LookAt`.
+//!
+//! All constraints read the world transforms of their targets before any constraint of the
+//! frame is applied. When a target is constrained itself, or is a child of a constrained
+//! entity, the constraint uses its transform from before that constraint was applied, so it
+//! lags one frame behind it.
+
+use cgmath::{Euler, EuclideanSpace, InnerSpace, Matrix4, Point3, Quaternion, SquareMatrix,
+             Vector3};
+use specs::{Component, DenseVecStorage, Entities, Entity, Fetch, Join, ReadStorage, System,
+            WriteStorage};
+
+use orientation::Orientation;
+use timing::Time;
+use transform::{LocalTransform, ParentHierarchy, Transform};
+use transform::systems::{parent_matrix, propagate_to_children};
+
+/// Which of the x, y and z axes a constraint applies to.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct Axes {
+    /// The x axis
+    pub x: bool,
+    /// The y axis
+    pub y: bool,
+    /// The z axis
+    pub z: bool,
+}
+
+impl Axes {
+    /// Selects the given axes.
+    pub fn new(x: bool, y: bool, z: bool) -> Self {
+        Axes { x, y, z }
+    }
+
+    /// Selects all axes.
+    pub fn all() -> Self {
+        Axes::new(true, true, true)
+    }
+
+    fn mask(&self) -> [bool; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+impl Default for Axes {
+    fn default() -> Self {
+        Axes::all()
+    }
+}
+
+/// Keeps the entity rotated towards the target entity, without rolling.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LookAt(pub Entity);
+
+impl Component for LookAt {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Moves the entity to the position of the target entity plus an offset.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Follow {
+    /// The followed entity
+    pub target: Entity,
+    /// The world space offset to the position of the target
+    pub offset: Vector3<f32>,
+    /// How many seconds it takes to cover most of the distance to the target, 0 sticks to it.
+    pub smoothing: f32,
+}
+
+impl Component for Follow {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Copies the world position of the target entity on the selected axes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CopyPosition {
+    /// The copied entity
+    pub target: Entity,
+    /// The copied axes
+    pub axes: Axes,
+}
+
+impl Component for CopyPosition {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Copies the world rotation of the target entity around the selected axes.
+///
+/// The rotations are compared as Euler angles, so copying only some axes can give
+/// surprising results for large rotations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CopyRotation {
+    /// The copied entity
+    pub target: Entity,
+    /// The copied axes
+    pub axes: Axes,
+}
+
+impl Component for CopyRotation {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Keeps the distance between the entity and the target entity within a range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DistanceLimit {
+    /// The entity the distance is measured to
+    pub target: Entity,
+    /// The minimum distance
+    pub min: f32,
+    /// The maximum distance
+    pub max: f32,
+}
+
+impl Component for DistanceLimit {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Applies the `LookAt`, `Follow`, `CopyPosition`, `CopyRotation` and `DistanceLimit`
+/// constraints, see the module documentation.
+///
+/// Without a `ParentHierarchy` resource, the constrained entities are treated as having no
+/// parent.
+///
+/// Added by the `TransformBundle` with name "constraint_system".
+#[derive(Default)]
+pub struct ConstraintSystem {
+    constrained: Vec<(Entity, LocalTransform)>,
+}
+
+impl ConstraintSystem {
+    /// Creates a new constraint system.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl<'a> System<'a> for ConstraintSystem {
+    type SystemData = (
+        Entities<'a>,
+        Fetch<'a, Time>,
+        Option<Fetch<'a, ParentHierarchy>>,
+        Option<Fetch<'a, Orientation>>,
+        ReadStorage<'a, LookAt>,
+        ReadStorage<'a, Follow>,
+        ReadStorage<'a, CopyPosition>,
+        ReadStorage<'a, CopyRotation>,
+        ReadStorage<'a, DistanceLimit>,
+        WriteStorage<'a, LocalTransform>,
+        WriteStorage<'a, Transform>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        use std::ops::Deref;
+
+        let (
+            entities,
+            time,
+            hierarchy,
+            orientation,
+            look_ats,
+            follows,
+            copy_positions,
+            copy_rotations,
+            distance_limits,
+            mut locals,
+            mut globals,
+        ) = data;
+
+        let default_orientation = Orientation::default();
+        let orientation = orientation.as_ref().map_or(&default_orientation, |o| &**o);
+        let hierarchy = hierarchy.as_ref().map(Deref::deref);
+        let delta = time.delta_seconds();
+
+        {
+            let constrained = look_ats.open().0 | follows.open().0 | copy_positions.open().0
+                | copy_rotations.open().0 | distance_limits.open().0;
+            let position = |target: Entity| globals.get(target).map(Transform::translation);
+
+            for (entity, global, _) in (&*entities, &globals, &constrained).join() {
+                let mut pose = global.decompose();
+
+                if let Some(copy) = copy_positions.get(entity) {
+                    if let Some(target) = position(copy.target) {
+                        for (axis, &copied) in copy.axes.mask().iter().enumerate() {
+                            if copied {
+                                pose.translation[axis] = target[axis];
+                            }
+                        }
+                    }
+                }
+
+                if let Some(follow) = follows.get(entity) {
+                    if let Some(target) = position(follow.target) {
+                        let alpha = if follow.smoothing > 0. {
+                            1. - (-delta / follow.smoothing).exp()
+                        } else {
+                            1.
+                        };
+                        let goal = target + follow.offset;
+                        pose.translation = pose.translation + (goal - pose.translation) * alpha;
+                    }
+                }
+
+                if let Some(limit) = distance_limits.get(entity) {
+                    if let Some(target) = position(limit.target) {
+                        let offset = pose.translation - target;
+                        let distance = offset.magnitude();
+                        let limited = distance.max(limit.min).min(limit.max);
+                        if distance > 0. && limited != distance {
+                            pose.translation = target + offset * (limited / distance);
+                        }
+                    }
+                }
+
+                if let Some(copy) = copy_rotations.get(entity) {
+                    if let Some(target) = globals.get(copy.target) {
+                        let target = Euler::from(target.rotation());
+                        let mut rotation = Euler::from(pose.rotation);
+                        let mask = copy.axes.mask();
+                        if mask[0] {
+                            rotation.x = target.x;
+                        }
+                        if mask[1] {
+                            rotation.y = target.y;
+                        }
+                        if mask[2] {
+                            rotation.z = target.z;
+                        }
+                        pose.rotation = Quaternion::from(rotation);
+                    }
+                }
+
+                if let Some(look_at) = look_ats.get(entity) {
+                    if let Some(target) = position(look_at.0) {
+                        if target != pose.translation {
+                            pose.look_at(orientation, Point3::from_vec(target));
+                        }
+                    }
+                }
+
+                self.constrained.push((entity, pose));
+            }
+        }
+
+        for (entity, pose) in self.constrained.drain(..) {
+            let world = pose.matrix();
+            // Rewriting an unchanged pose would flag the `LocalTransform` every frame, and
+            // let it drift under non-uniformly scaled parents.
+            if globals.get(entity).map_or(false, |global| near(global.0, world)) {
+                continue;
+            }
+
+            let inverse = match parent_matrix(hierarchy, &globals, entity).invert() {
+                Some(inverse) => inverse,
+                None => continue,
+            };
+
+            locals.insert(entity, Transform(inverse * world).decompose());
+            if let Some(global) = globals.get_mut(entity) {
+                global.0 = world;
+            }
+            propagate_to_children(hierarchy, &mut globals, entity, |child| {
+                locals.get(child).map(LocalTransform::matrix)
+            });
+        }
+    }
+}
+
+/// Whether two matrices are equal, except for floating point errors.
+fn near(left: Matrix4<f32>, right: Matrix4<f32>) -> bool {
+    let left: [[f32; 4]; 4] = left.into();
+    let right: [[f32; 4]; 4] = right.into();
+    left.iter().zip(right.iter()).all(|(left, right)| {
+        left.iter()
+            .zip(right.iter())
+            .all(|(left, right)| (left - right).abs() <= 1.0e-5 * left.abs().max(1.))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Deg, One, Vector3};
+    use shred::RunNow;
+    use specs::World;
+
+    use super::*;
+    use transform::{Parent, TransformSystem};
+    use transform::systems::tests::transform_world;
+
+    fn constraint_world() -> World {
+        let (mut world, _) = transform_world();
+        world.register::<LookAt>();
+        world.register::<Follow>();
+        world.register::<CopyPosition>();
+        world.register::<CopyRotation>();
+        world.register::<DistanceLimit>();
+        world.add_resource(Time::default());
+        world
+    }
+
+    fn create(world: &mut World, local: LocalTransform) -> Entity {
+        world
+            .create_entity()
+            .with(Transform(local.matrix()))
+            .with(local)
+            .build()
+    }
+
+    fn assert_near(left: Matrix4<f32>, right: Matrix4<f32>) {
+        let left: [[f32; 4]; 4] = left.into();
+        let right: [[f32; 4]; 4] = right.into();
+        for (left, right) in left.iter().zip(right.iter()) {
+            for (left, right) in left.iter().zip(right.iter()) {
+                assert!((left - right).abs() < 1.0e-4, "{:?} != {:?}", left, right);
+            }
+        }
+    }
+
+    #[test]
+    fn position_constraints() {
+        let mut world = constraint_world();
+
+        let mut target = LocalTransform::default();
+        target.translation = Vector3::new(1.0, 2.0, 3.0);
+        let target = create(&mut world, target);
+
+        let follower = create(&mut world, LocalTransform::default());
+        world.write::<Follow>().insert(
+            follower,
+            Follow {
+                target,
+                offset: Vector3::new(0.0, 10.0, 0.0),
+                smoothing: 0.0,
+            },
+        );
+        world.write::<DistanceLimit>().insert(
+            follower,
+            DistanceLimit {
+                target,
+                min: 0.0,
+                max: 5.0,
+            },
+        );
+        let copier = create(&mut world, LocalTransform::default());
+        world.write::<CopyPosition>().insert(
+            copier,
+            CopyPosition {
+                target,
+                axes: Axes::new(true, false, true),
+            },
+        );
+
+        ConstraintSystem::new().run_now(&world.res);
+
+        let locals = world.read::<LocalTransform>();
+        let globals = world.read::<Transform>();
+        assert_eq!(locals.get(follower).unwrap().translation, Vector3::new(1.0, 7.0, 3.0));
+        assert_eq!(globals.get(follower).unwrap().translation(), Vector3::new(1.0, 7.0, 3.0));
+        assert_eq!(locals.get(copier).unwrap().translation, Vector3::new(1.0, 0.0, 3.0));
+    }
+
+    #[test]
+    fn look_at() {
+        let mut world = constraint_world();
+
+        let mut target = LocalTransform::default();
+        target.translation = Vector3::new(0.0, 5.0, 5.0);
+        let target = create(&mut world, target);
+
+        // The looking entity is moved by its parent, the child of it has to follow.
+        let mut parent = LocalTransform::default();
+        parent.translation = Vector3::new(2.0, 0.0, 0.0);
+        let parent = create(&mut world, parent);
+        let looker = create(&mut world, LocalTransform::default());
+        let mut child = LocalTransform::default();
+        child.translation = Vector3::new(0.0, 0.0, 1.0);
+        let child = create(&mut world, child);
+        world.write::<Parent>().insert(looker, Parent { entity: parent });
+        world.write::<Parent>().insert(child, Parent { entity: looker });
+        world.write::<LookAt>().insert(looker, LookAt(target));
+
+        TransformSystem::new().run_now(&world.res);
+        ConstraintSystem::new().run_now(&world.res);
+
+        let mut expected = LocalTransform::default();
+        expected.translation = Vector3::new(2.0, 0.0, 0.0);
+        expected.look_at(&Orientation::default(), Point3::new(0.0, 5.0, 5.0));
+
+        let locals = world.read::<LocalTransform>();
+        let globals = world.read::<Transform>();
+        let looker_local = locals.get(looker).unwrap();
+        assert_near(looker_local.matrix(), expected.rotation.into());
+        assert_near(globals.get(looker).unwrap().0, expected.matrix());
+        assert_near(
+            globals.get(child).unwrap().0,
+            expected.matrix() * locals.get(child).unwrap().matrix(),
+        );
+    }
+
+    #[test]
+    fn copy_rotation() {
+        let mut world = constraint_world();
+
+        let mut target = LocalTransform::default();
+        target.rotation = Euler::new(Deg(30.0), Deg(0.0), Deg(0.0)).into();
+        let target = create(&mut world, target);
+
+        let x = create(&mut world, LocalTransform::default());
+        world.write::<CopyRotation>().insert(
+            x,
+            CopyRotation {
+                target,
+                axes: Axes::new(true, false, false),
+            },
+        );
+        let yz = create(&mut world, LocalTransform::default());
+        world.write::<CopyRotation>().insert(
+            yz,
+            CopyRotation {
+                target,
+                axes: Axes::new(false, true, true),
+            },
+        );
+
+        ConstraintSystem::new().run_now(&world.res);
+
+        let mut expected = LocalTransform::default();
+        expected.rotation = Euler::new(Deg(30.0), Deg(0.0), Deg(0.0)).into();
+        let globals = world.read::<Transform>();
+        assert_near(globals.get(x).unwrap().0, expected.matrix());
+        assert_near(globals.get(yz).unwrap().0, Matrix4::one());
+    }
+
+    #[test]
+    fn unchanged_pose() {
+        let mut world = constraint_world();
+
+        let mut local = LocalTransform::default();
+        local.translation = Vector3::new(1.0, 2.0, 3.0);
+        let target = create(&mut world, local.clone());
+        let follower = create(&mut world, local);
+        world.write::<Follow>().insert(
+            follower,
+            Follow {
+                target,
+                offset: Vector3::new(0.0, 0.0, 0.0),
+                smoothing: 0.0,
+            },
+        );
+        (&mut world.write::<LocalTransform>()).open().1.clear_flags();
+
+        ConstraintSystem::new().run_now(&world.res);
+
+        assert!(!world.read::<LocalTransform>().open().1.flagged(follower));
+    }
+
+    #[test]
+    fn without_hierarchy() {
+        let mut world = World::new();
+        world.register::<LocalTransform>();
+        world.register::<Transform>();
+        world.register::<LookAt>();
+        world.register::<Follow>();
+        world.register::<CopyPosition>();
+        world.register::<CopyRotation>();
+        world.register::<DistanceLimit>();
+        world.add_resource(Time::default());
+
+        let mut target = LocalTransform::default();
+        target.translation = Vector3::new(1.0, 2.0, 3.0);
+        let target = create(&mut world, target);
+        let follower = create(&mut world, LocalTransform::default());
+        world.write::<Follow>().insert(
+            follower,
+            Follow {
+                target,
+                offset: Vector3::new(0.0, 1.0, 0.0),
+                smoothing: 0.0,
+            },
+        );
+
+        ConstraintSystem::new().run_now(&world.res);
+
+        let locals = world.read::<LocalTransform>();
+        let globals = world.read::<Transform>();
+        assert_eq!(locals.get(follower).unwrap().translation, Vector3::new(1.0, 3.0, 3.0));
+        assert_eq!(globals.get(follower).unwrap().translation(), Vector3::new(1.0, 3.0, 3.0));
+    }
+}
@@ -0,0 +1,180 @@
+//! Parent and children lookup for entities.
+
+use fnv::FnvHashMap as HashMap;
+use shrev::{EventChannel, ReaderId};
+use specs::{EntitiesRes, Entity};
+
+/// What happens to the children of a deleted entity.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum OrphanPolicy {
+    /// The children are deleted too, together with all their children.
+    Delete,
+    /// The `Parent` of the children is removed, and their `LocalTransform` is set
+    /// so they keep their last world transform.
+    Detach,
+    /// The children keep their `Parent`, pointing to the deleted entity. They are
+    /// transformed as if they had no parent.
+    Keep,
+}
+
+impl Default for OrphanPolicy {
+    fn default() -> Self {
+        OrphanPolicy::Delete
+    }
+}
+
+/// A change of the hierarchy, written to `ParentHierarchy::changed`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HierarchyEvent {
+    /// The entity got a new parent.
+    Modified(Entity),
+    /// The entity no longer has a parent, or was deleted.
+    Removed(Entity),
+}
+
+/// A resource mapping entities to their parent and children.
+///
+/// It is maintained by the `TransformSystem` from the `Parent` components, so changes
+/// to `Parent` show up here after the system ran. The system also applies the
+/// `OrphanPolicy` to the children of deleted entities.
+pub struct ParentHierarchy {
+    parents: HashMap<Entity, Entity>,
+    children: HashMap<Entity, Vec<Entity>>,
+    orphan_policy: OrphanPolicy,
+    changed: EventChannel<HierarchyEvent>,
+}
+
+impl Default for ParentHierarchy {
+    fn default() -> Self {
+        ParentHierarchy::with_orphan_policy(OrphanPolicy::default())
+    }
+}
+
+impl ParentHierarchy {
+    /// Creates an empty hierarchy, deleting the children of deleted entities.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates an empty hierarchy with the given orphan policy.
+    pub fn with_orphan_policy(orphan_policy: OrphanPolicy) -> Self {
+        ParentHierarchy {
+            parents: HashMap::default(),
+            children: HashMap::default(),
+            orphan_policy,
+            changed: EventChannel::new(),
+        }
+    }
+
+    /// Returns what happens to the children of deleted entities.
+    pub fn orphan_policy(&self) -> OrphanPolicy {
+        self.orphan_policy
+    }
+
+    /// Sets what happens to the children of deleted entities.
+    pub fn set_orphan_policy(&mut self, orphan_policy: OrphanPolicy) {
+        self.orphan_policy = orphan_policy;
+    }
+
+    /// Returns the parent of `entity`, if it has one.
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.parents.get(&entity).cloned()
+    }
+
+    /// Returns the direct children of `entity`.
+    pub fn children(&self, entity: Entity) -> &[Entity] {
+        self.children
+            .get(&entity)
+            .map(|children| &children[..])
+            .unwrap_or(&[])
+    }
+
+    /// Returns all children of `entity`, their children and so on.
+    ///
+    /// Parents are listed before their children.
+    pub fn all_children(&self, entity: Entity) -> Vec<Entity> {
+        let mut all = self.children(entity).to_vec();
+        let mut index = 0;
+        while index < all.len() {
+            let children = self.children(all[index]);
+            all.extend_from_slice(children);
+            index += 1;
+        }
+
+        all
+    }
+
+    /// Deletes `entity` and all its children, their children and so on.
+    pub fn delete_recursive(&self, entities: &EntitiesRes, entity: Entity) {
+        for child in self.all_children(entity) {
+            let _ = entities.delete(child);
+        }
+        let _ = entities.delete(entity);
+    }
+
+    /// Returns the channel of hierarchy changes, read it with a reader
+    /// from `track`.
+    pub fn changed(&self) -> &EventChannel<HierarchyEvent> {
+        &self.changed
+    }
+
+    /// Registers a reader for the `changed` channel.
+    pub fn track(&mut self) -> ReaderId {
+        self.changed.register_reader()
+    }
+
+    /// Returns the entities which currently have a parent.
+    pub(crate) fn entities_with_parent(&self) -> Vec<(Entity, Entity)> {
+        self.parents
+            .iter()
+            .map(|(child, parent)| (*child, *parent))
+            .collect()
+    }
+
+    /// Returns the parents which have at least one child.
+    pub(crate) fn entities_with_children(&self) -> Vec<Entity> {
+        self.children.keys().cloned().collect()
+    }
+
+    pub(crate) fn set_parent(&mut self, child: Entity, parent: Entity) {
+        if let Some(old) = self.parents.insert(child, parent) {
+            self.remove_child(old, child);
+        }
+        self.children
+            .entry(parent)
+            .or_insert_with(Vec::new)
+            .push(child);
+        self.changed.single_write(HierarchyEvent::Modified(child));
+    }
+
+    pub(crate) fn remove(&mut self, child: Entity) {
+        if let Some(parent) = self.parents.remove(&child) {
+            self.remove_child(parent, child);
+            self.changed.single_write(HierarchyEvent::Removed(child));
+        }
+    }
+
+    /// Forgets the children of a deleted parent, returning them.
+    pub(crate) fn remove_parent(&mut self, parent: Entity) -> Vec<Entity> {
+        let children = self.children.remove(&parent).unwrap_or_default();
+        for child in &children {
+            self.parents.remove(child);
+            self.changed.single_write(HierarchyEvent::Removed(*child));
+        }
+
+        children
+    }
+
+    fn remove_child(&mut self, parent: Entity, child: Entity) {
+        let empty = match self.children.get_mut(&parent) {
+            Some(children) => {
+                children.retain(|c| *c != child);
+                children.is_empty()
+            }
+            None => false,
+        };
+        if empty {
+            self.children.remove(&parent);
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! Smooth rendering of entities moved by fixed updates.
+//!
+//! Entities opt in with the `InterpolatedTransform` component. The `TransformSnapshotSystem`
+//! stores the previous and current `LocalTransform` in every frame in which fixed updates ran.
+//! The `TransformInterpolationSystem` runs after it, and replaces their `Transform`, and the
+//! ones of their children, with the blend of the two snapshots given by
+//! `Time::interpolation_alpha`.
+//!
+//! Both systems are added by `TransformBundle::with_interpolation`.
+//!
+//! ```ignore
+//! let game = Application::build("assets/", NullState)?
+//!     .with_bundle(TransformBundle::new().with_interpolation())?
+//!     .with_fixed(PhysicsSystem, "physics", &[]);
+//! ```
+
+use specs::{Component, DenseVecStorage, Entities, Fetch, Join, ReadStorage, System,
+            WriteStorage};
+
+use timing::Time;
+use transform::{LocalTransform, ParentHierarchy, Transform};
+use transform::systems::{parent_matrix, propagate_to_children};
+
+/// Marks an entity as interpolated between fixed updates, and stores the last two
+/// `LocalTransform`s of the fixed updates.
+///
+/// Insert it again to skip the interpolation once, for example when teleporting the entity.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InterpolatedTransform {
+    previous: Option<LocalTransform>,
+    current: Option<LocalTransform>,
+}
+
+impl InterpolatedTransform {
+    /// Creates a new component without snapshots. The entity is not interpolated until
+    /// the first fixed update after inserting it.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the `LocalTransform` of the second to last fixed update.
+    pub fn previous(&self) -> Option<&LocalTransform> {
+        self.previous.as_ref()
+    }
+
+    /// Returns the `LocalTransform` of the last fixed update.
+    pub fn current(&self) -> Option<&LocalTransform> {
+        self.current.as_ref()
+    }
+
+    /// Stores a new snapshot, the current one becomes the previous one.
+    pub fn push(&mut self, local: LocalTransform) {
+        self.previous = Some(self.current.take().unwrap_or_else(|| local.clone()));
+        self.current = Some(local);
+    }
+
+    /// Blends the previous and the current snapshot, with `alpha` going from 0 (previous)
+    /// to 1 (current).
+    pub fn interpolate(&self, alpha: f32) -> Option<LocalTransform> {
+        let (previous, current) = match (self.previous.as_ref(), self.current.as_ref()) {
+            (Some(previous), Some(current)) => (previous, current),
+            _ => return None,
+        };
+
+        let translation = current.translation - previous.translation;
+        Some(LocalTransform {
+            rotation: previous.rotation.slerp(current.rotation, alpha),
+            scale: previous.scale + (current.scale - previous.scale) * alpha,
+            translation: previous.translation + translation * alpha,
+        })
+    }
+}
+
+impl Component for InterpolatedTransform {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Stores the `LocalTransform` of every entity with an `InterpolatedTransform`, in the frames
+/// in which fixed updates ran.
+///
+/// The transforms are expected to only change in fixed updates, so the last snapshot is the
+/// state before the last fixed update. When several fixed updates run in one frame, the
+/// entities are interpolated from the state before the first one.
+///
+/// Added by `TransformBundle::with_interpolation` with name "transform_snapshot_system".
+#[derive(Default)]
+pub struct TransformSnapshotSystem;
+
+impl TransformSnapshotSystem {
+    /// Creates a new transform snapshot system.
+    pub fn new() -> Self {
+        TransformSnapshotSystem
+    }
+}
+
+impl<'a> System<'a> for TransformSnapshotSystem {
+    type SystemData = (
+        Fetch<'a, Time>,
+        ReadStorage<'a, LocalTransform>,
+        WriteStorage<'a, InterpolatedTransform>,
+    );
+
+    fn run(&mut self, (time, locals, mut interpolated): Self::SystemData) {
+        if time.fixed_steps() == 0 {
+            return;
+        }
+
+        for (local, interpolated) in (&locals, &mut interpolated).join() {
+            interpolated.push(local.clone());
+        }
+    }
+}
+
+/// Replaces the `Transform` of every entity with an `InterpolatedTransform` by the blend of
+/// its snapshots, and updates the `Transform` of its children to match. Children with an
+/// `InterpolatedTransform` of their own use their blended `LocalTransform`.
+///
+/// Without a `ParentHierarchy` resource, the interpolated entities are treated as having no
+/// parent.
+///
+/// Added by `TransformBundle::with_interpolation` with name "transform_interpolation_system".
+#[derive(Default)]
+pub struct TransformInterpolationSystem;
+
+impl TransformInterpolationSystem {
+    /// Creates a new transform interpolation system.
+    pub fn new() -> Self {
+        TransformInterpolationSystem
+    }
+}
+
+impl<'a> System<'a> for TransformInterpolationSystem {
+    type SystemData = (
+        Entities<'a>,
+        Fetch<'a, Time>,
+        Option<Fetch<'a, ParentHierarchy>>,
+        ReadStorage<'a, LocalTransform>,
+        ReadStorage<'a, InterpolatedTransform>,
+        WriteStorage<'a, Transform>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, time, hierarchy, locals, interpolated, mut globals): Self::SystemData,
+    ) {
+        use std::ops::Deref;
+
+        let hierarchy = hierarchy.as_ref().map(Deref::deref);
+        let parent_of = |entity| hierarchy.and_then(|hierarchy| hierarchy.parent(entity));
+        let alpha = time.interpolation_alpha();
+        let local_matrix = |entity| match interpolated
+            .get(entity)
+            .and_then(|interpolated| interpolated.interpolate(alpha))
+        {
+            Some(local) => Some(local.matrix()),
+            None => locals.get(entity).map(LocalTransform::matrix),
+        };
+
+        for (entity, _) in (&*entities, &interpolated).join() {
+            // Entities below another interpolated entity are updated together with it.
+            let mut ancestor = parent_of(entity);
+            while let Some(parent) = ancestor {
+                if interpolated.get(parent).is_some() {
+                    break;
+                }
+                ancestor = parent_of(parent);
+            }
+            if ancestor.is_some() {
+                continue;
+            }
+
+            let parent = parent_matrix(hierarchy, &globals, entity);
+            if let (Some(local), Some(global)) = (local_matrix(entity), globals.get_mut(entity)) {
+                global.0 = parent * local;
+            }
+            propagate_to_children(hierarchy, &mut globals, entity, &local_matrix);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Vector3;
+    use shred::RunNow;
+    use specs::World;
+
+    use super::*;
+    use transform::Parent;
+    use transform::systems::tests::transform_world;
+
+    fn frame(world: &mut World, delta_seconds: f32) {
+        let mut time = world.write_resource::<Time>();
+        time.set_delta_seconds(delta_seconds);
+        time.accumulate_fixed_time();
+        while time.step_fixed_update() {}
+    }
+
+    #[test]
+    fn interpolate_between_fixed_updates() {
+        let (mut world, mut system) = transform_world();
+        world.register::<InterpolatedTransform>();
+        let mut time = Time::default();
+        time.set_fixed_seconds(1.0);
+        world.add_resource(time);
+
+        // Created first, so it is joined before its interpolated parent.
+        let interpolated_child = world.create_entity().build();
+        let entity = world
+            .create_entity()
+            .with(LocalTransform::default())
+            .with(Transform::default())
+            .with(InterpolatedTransform::new())
+            .build();
+        let child = world
+            .create_entity()
+            .with(LocalTransform::default())
+            .with(Transform::default())
+            .with(Parent { entity })
+            .build();
+        world
+            .write::<LocalTransform>()
+            .insert(interpolated_child, LocalTransform::default());
+        world
+            .write::<Transform>()
+            .insert(interpolated_child, Transform::default());
+        world
+            .write::<Parent>()
+            .insert(interpolated_child, Parent { entity });
+        world
+            .write::<InterpolatedTransform>()
+            .insert(interpolated_child, InterpolatedTransform::new());
+
+        let mut snapshot = TransformSnapshotSystem::new();
+        let mut interpolation = TransformInterpolationSystem::new();
+        let mut run = |world: &mut World| {
+            system.run_now(&world.res);
+            snapshot.run_now(&world.res);
+            interpolation.run_now(&world.res);
+        };
+
+        // A fixed update which leaves everything at the origin.
+        frame(&mut world, 1.0);
+        run(&mut world);
+
+        // A fixed update which moves the entity and its interpolated child, a quarter of the
+        // next fixed update has passed.
+        for entity in &[entity, interpolated_child] {
+            world.write::<LocalTransform>().get_mut(*entity).unwrap().translation =
+                Vector3::new(4.0, 0.0, 0.0);
+        }
+        frame(&mut world, 1.25);
+        run(&mut world);
+
+        let globals = world.read::<Transform>();
+        assert_eq!(globals.get(entity).unwrap().translation(), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(globals.get(child).unwrap().translation(), Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(
+            globals.get(interpolated_child).unwrap().translation(),
+            Vector3::new(2.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn without_hierarchy() {
+        let mut world = World::new();
+        world.register::<LocalTransform>();
+        world.register::<Transform>();
+        world.register::<InterpolatedTransform>();
+        let mut time = Time::default();
+        time.set_fixed_seconds(1.0);
+        world.add_resource(time);
+
+        let entity = world
+            .create_entity()
+            .with(LocalTransform::default())
+            .with(Transform::default())
+            .with(InterpolatedTransform::new())
+            .build();
+
+        let mut snapshot = TransformSnapshotSystem::new();
+        let mut interpolation = TransformInterpolationSystem::new();
+        frame(&mut world, 1.0);
+        snapshot.run_now(&world.res);
+        interpolation.run_now(&world.res);
+
+        world.write::<LocalTransform>().get_mut(entity).unwrap().translation =
+            Vector3::new(4.0, 0.0, 0.0);
+        frame(&mut world, 1.25);
+        snapshot.run_now(&world.res);
+        interpolation.run_now(&world.res);
+
+        assert_eq!(
+            world.read::<Transform>().get(entity).unwrap().translation(),
+            Vector3::new(1.0, 0.0, 0.0)
+        );
+    }
+}
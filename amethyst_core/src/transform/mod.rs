@@ -2,8 +2,16 @@
 
 pub use self::bundle::TransformBundle;
 pub use self::components::*;
+pub use self::constraints::{Axes, ConstraintSystem, CopyPosition, CopyRotation, DistanceLimit,
+                            Follow, LookAt};
+pub use self::hierarchy::{HierarchyEvent, OrphanPolicy, ParentHierarchy};
+pub use self::interpolation::{InterpolatedTransform, TransformInterpolationSystem,
+                              TransformSnapshotSystem};
 pub use self::systems::*;
 
 pub mod components;
+pub mod constraints;
+pub mod hierarchy;
+pub mod interpolation;
 pub mod systems;
 pub mod bundle;
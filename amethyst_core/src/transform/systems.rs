@@ -1,12 +1,26 @@
 //! Scene graph system and types
 
+use cgmath::{Matrix4, One, SquareMatrix};
 use fnv::{FnvHashMap as HashMap, FnvHashSet as HashSet};
 use hibitset::BitSet;
-use specs::{Entities, Entity, Join, System, WriteStorage};
-use transform::{LocalTransform, Parent, Transform};
+use shred::{ResourceId, Resources, SystemData};
+use specs::{Component, Entities, EntitiesRes, Entity, FetchMut, Join, MaskedStorage, System,
+            WriteStorage};
+use transform::{LocalTransform, OrphanPolicy, Parent, ParentHierarchy, Reparent,
+                SetWorldTransform, Transform, Transform2D};
 
 /// Handles updating `Transform` components based on the `LocalTransform`
-/// component and parents.
+/// or `Transform2D` component and parents.
+///
+/// Also keeps the `ParentHierarchy` up to date, applies its `OrphanPolicy`
+/// to the children of deleted entities, and handles `Reparent` and `SetWorldTransform`
+/// requests before computing the transforms.
+///
+/// The `ParentHierarchy` resource is optional, without it the children of deleted entities
+/// are deleted, like with `OrphanPolicy::Delete`. Only `LocalTransform`, `Parent` and
+/// `Transform` have to be registered, `Transform2D`s and `Reparent` and `SetWorldTransform`
+/// requests are only handled if their components are registered. The `TransformBundle`
+/// registers all of them.
 #[derive(Default)]
 pub struct TransformSystem {
     /// Map of entities to index in sorted vec.
@@ -45,14 +59,38 @@ impl<'a> System<'a> for TransformSystem {
         WriteStorage<'a, LocalTransform>,
         WriteStorage<'a, Parent>,
         WriteStorage<'a, Transform>,
+        Option<FetchMut<'a, ParentHierarchy>>,
+        OptionalStorage<'a, Reparent>,
+        OptionalStorage<'a, SetWorldTransform>,
+        OptionalStorage<'a, Transform2D>,
     );
-    fn run(&mut self, (entities, mut locals, mut parents, mut globals): Self::SystemData) {
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut locals,
+            mut parents,
+            mut globals,
+            mut hierarchy,
+            reparents,
+            world_poses,
+            transforms_2d,
+        ) = data;
+
         #[cfg(feature = "profiler")]
         profile_scope!("transform_system");
 
         // Clear dirty flags on `Transform` storage, before updates go in
         (&mut globals).open().1.clear_flags();
 
+        // Apply modified `Transform2D`s, the rest of the system only uses `LocalTransform`.
+        if let Some(mut transforms_2d) = transforms_2d.into_inner() {
+            for (entity, transform) in (&*entities, transforms_2d.open().1).join() {
+                locals.insert(entity, transform.clone().into());
+            }
+
+            (&mut transforms_2d).open().1.clear_flags();
+        }
+
         {
             for (entity, parent) in (&*entities, parents.open().1).join() {
                 if parent.entity == entity {
@@ -61,13 +99,98 @@ impl<'a> System<'a> for TransformSystem {
             }
 
             for entity in self.remove_parent.iter() {
-                eprintln!("Entity was its own parent: {:?}", entity);
+                warn!("Entity was its own parent: {:?}", entity);
                 parents.remove(*entity);
             }
 
             self.remove_parent.clear();
         }
 
+        // Requests are applied relative to the current local transforms of the parents,
+        // so they are exact even if a parent moved since the last run.
+        if let Some(mut reparents) = reparents.into_inner() {
+            let requests = (&*entities, &reparents)
+                .join()
+                .map(|(entity, reparent)| (entity, reparent.0))
+                .collect::<Vec<_>>();
+            for (entity, parent) in requests {
+                reparents.remove(entity);
+                if let Some(parent) = parent {
+                    if has_ancestor(parent, entity, &entities, &parents) {
+                        warn!(
+                            "Entity can't be reparented to itself or one of its children: {:?}",
+                            entity
+                        );
+                        continue;
+                    }
+                }
+
+                let world = world_matrix(entity, &entities, &locals, &parents);
+                match parent {
+                    Some(parent) => {
+                        parents.insert(entity, Parent { entity: parent });
+                    }
+                    None => {
+                        parents.remove(entity);
+                    }
+                }
+                set_world_matrix(entity, world, &entities, &mut locals, &parents);
+            }
+        }
+
+        if let Some(mut world_poses) = world_poses.into_inner() {
+            let requests = (&*entities, &world_poses)
+                .join()
+                .map(|(entity, pose)| (entity, pose.0.matrix()))
+                .collect::<Vec<_>>();
+            for (entity, world) in requests {
+                world_poses.remove(entity);
+                set_world_matrix(entity, world, &entities, &mut locals, &parents);
+            }
+        }
+
+        if let Some(ref mut hierarchy) = hierarchy {
+            // Forget children which were deleted or lost their parent.
+            for (child, _) in hierarchy.entities_with_parent() {
+                if !entities.is_alive(child) || parents.get(child).is_none() {
+                    hierarchy.remove(child);
+                }
+            }
+
+            // Orphans which are kept don't belong to their deleted parent anymore.
+            let keep = hierarchy.orphan_policy() == OrphanPolicy::Keep;
+            for (entity, parent) in (&*entities, parents.open().1).join() {
+                if hierarchy.parent(entity) != Some(parent.entity)
+                    && !(keep && !entities.is_alive(parent.entity))
+                {
+                    hierarchy.set_parent(entity, parent.entity);
+                }
+            }
+
+            for parent in hierarchy.entities_with_children() {
+                if entities.is_alive(parent) {
+                    continue;
+                }
+
+                match hierarchy.orphan_policy() {
+                    OrphanPolicy::Delete => for child in hierarchy.all_children(parent) {
+                        let _ = entities.delete(child);
+                        self.dead.insert(child);
+                        hierarchy.remove(child);
+                    },
+                    OrphanPolicy::Detach => for child in hierarchy.remove_parent(parent) {
+                        if let Some(global) = globals.get(child).cloned() {
+                            locals.insert(child, global.decompose());
+                        }
+                        parents.remove(child);
+                    },
+                    OrphanPolicy::Keep => {
+                        hierarchy.remove_parent(parent);
+                    }
+                }
+            }
+        }
+
         {
             // Checks for entities with a modified local transform or a modified parent, but isn't initialized yet.
             let filter = locals.open().0 & parents.open().0 & !&self.init; // has a local, parent, and isn't initialized.
@@ -128,13 +251,25 @@ impl<'a> System<'a> for TransformSystem {
                         }
                     }
 
-                    // Kill the entity if the parent is dead.
                     if self.dead.contains(&parent.entity) || !entities.is_alive(parent.entity) {
-                        self.remove(index);
-                        let _ = entities.delete(entity);
-                        self.dead.insert(entity);
+                        // Without a `ParentHierarchy` orphans are deleted, like with the
+                        // default `OrphanPolicy::Delete`.
+                        if hierarchy.is_none() {
+                            self.remove(index);
+                            let _ = entities.delete(entity);
+                            self.dead.insert(entity);
+
+                            // Re-try index because swapped with last element.
+                            continue;
+                        }
 
-                        // Re-try index because swapped with last element.
+                        // The orphan policy kept this entity, so it is transformed as if it
+                        // had no parent.
+                        if let Some(global) = globals.get_mut(entity) {
+                            global.0 = local.matrix();
+                        }
+
+                        index += 1;
                         continue;
                     }
 
@@ -178,12 +313,169 @@ impl<'a> System<'a> for TransformSystem {
     }
 }
 
+/// A `WriteStorage` which is only fetched if its component is registered.
+pub struct OptionalStorage<'a, T: Component>(Option<WriteStorage<'a, T>>);
+
+impl<'a, T: Component> OptionalStorage<'a, T> {
+    /// Returns the storage, or `None` if the component isn't registered.
+    pub fn into_inner(self) -> Option<WriteStorage<'a, T>> {
+        self.0
+    }
+}
+
+impl<'a, T: Component> SystemData<'a> for OptionalStorage<'a, T> {
+    fn fetch(res: &'a Resources, id: usize) -> Self {
+        if res.has_value(ResourceId::new_with_id::<MaskedStorage<T>>(id)) {
+            OptionalStorage(Some(WriteStorage::fetch(res, id)))
+        } else {
+            OptionalStorage(None)
+        }
+    }
+
+    fn reads(id: usize) -> Vec<ResourceId> {
+        WriteStorage::<T>::reads(id)
+    }
+
+    fn writes(id: usize) -> Vec<ResourceId> {
+        WriteStorage::<T>::writes(id)
+    }
+}
+
+/// Checks if `ancestor` is `entity`, its parent, the parent of its parent and so on.
+fn has_ancestor(
+    entity: Entity,
+    ancestor: Entity,
+    entities: &EntitiesRes,
+    parents: &WriteStorage<Parent>,
+) -> bool {
+    let mut visited = HashSet::default();
+    let mut current = entity;
+    while visited.insert(current) {
+        if current == ancestor {
+            return true;
+        }
+
+        match parents.get(current) {
+            Some(parent) if entities.is_alive(parent.entity) => current = parent.entity,
+            _ => break,
+        }
+    }
+
+    false
+}
+
+/// Computes the world matrix of `entity` from the local transforms of it and its parents.
+///
+/// Dead parents end the chain, like they do for the orphans kept by `OrphanPolicy::Keep`.
+fn world_matrix(
+    entity: Entity,
+    entities: &EntitiesRes,
+    locals: &WriteStorage<LocalTransform>,
+    parents: &WriteStorage<Parent>,
+) -> Matrix4<f32> {
+    let mut matrix = Matrix4::one();
+    let mut visited = HashSet::default();
+    let mut current = entity;
+    loop {
+        if let Some(local) = locals.get(current) {
+            matrix = local.matrix() * matrix;
+        }
+        visited.insert(current);
+
+        match parents.get(current) {
+            Some(parent) if entities.is_alive(parent.entity) => {
+                if visited.contains(&parent.entity) {
+                    warn!("Entity is part of a parent cycle: {:?}", entity);
+                    break;
+                }
+                current = parent.entity;
+            }
+            _ => break,
+        }
+    }
+
+    matrix
+}
+
+/// Sets the `LocalTransform` of `entity` so its world matrix becomes `world`.
+fn set_world_matrix(
+    entity: Entity,
+    world: Matrix4<f32>,
+    entities: &EntitiesRes,
+    locals: &mut WriteStorage<LocalTransform>,
+    parents: &WriteStorage<Parent>,
+) {
+    let parent_world = match parents.get(entity) {
+        Some(parent) if entities.is_alive(parent.entity) => {
+            world_matrix(parent.entity, entities, locals, parents)
+        }
+        _ => Matrix4::one(),
+    };
+
+    match parent_world.invert() {
+        Some(inverse) => {
+            locals.insert(entity, Transform(inverse * world).decompose());
+        }
+        None => warn!(
+            "Can't place entity {:?} in world space, its parent has a zero scale",
+            entity
+        ),
+    }
+}
+
+/// Returns the world matrix of the parent of `entity`, or the identity if it has none.
+///
+/// Without a `ParentHierarchy`, no entity has a parent.
+pub(crate) fn parent_matrix(
+    hierarchy: Option<&ParentHierarchy>,
+    globals: &WriteStorage<Transform>,
+    entity: Entity,
+) -> Matrix4<f32> {
+    hierarchy
+        .and_then(|hierarchy| hierarchy.parent(entity))
+        .and_then(|parent| globals.get(parent))
+        .map(|global| global.0)
+        .unwrap_or_else(Matrix4::one)
+}
+
+/// Updates the `Transform`s of all children of `entity` after its `Transform` was changed
+/// outside of the `TransformSystem`. `local` returns the local matrix of a child.
+pub(crate) fn propagate_to_children<F>(
+    hierarchy: Option<&ParentHierarchy>,
+    globals: &mut WriteStorage<Transform>,
+    entity: Entity,
+    local: F,
+) where
+    F: Fn(Entity) -> Option<Matrix4<f32>>,
+{
+    let hierarchy = match hierarchy {
+        Some(hierarchy) => hierarchy,
+        None => return,
+    };
+
+    // Children are listed after their parents, so their parents are already updated.
+    for child in hierarchy.all_children(entity) {
+        let parent = match hierarchy.parent(child).and_then(|p| globals.get(p)) {
+            Some(global) => global.0,
+            None => continue,
+        };
+        let local = match local(child) {
+            Some(local) => local,
+            None => continue,
+        };
+        if let Some(global) = globals.get_mut(child) {
+            global.0 = parent * local;
+        }
+    }
+}
+
 #[cfg(test)]
-mod tests {
-    use cgmath::{Decomposed, Matrix4, One, Quaternion, Vector3, Zero};
+pub(crate) mod tests {
+    use cgmath::{Decomposed, Matrix4, One, Quaternion, Vector2, Vector3, Zero};
     use shred::RunNow;
     use specs::World;
-    use transform::{LocalTransform, Parent, Transform, TransformSystem};
+    use transform::{LocalTransform, OrphanPolicy, Parent, ParentHierarchy, Reparent,
+                    SetWorldTransform, Transform, Transform2D, TransformSystem};
     //use quickcheck::{Arbitrary, Gen};
 
     // If this works, then all other tests should work.
@@ -222,11 +514,15 @@ mod tests {
         );
     }
 
-    fn transform_world<'a, 'b>() -> (World, TransformSystem) {
+    pub(crate) fn transform_world() -> (World, TransformSystem) {
         let mut world = World::new();
         world.register::<LocalTransform>();
         world.register::<Transform>();
         world.register::<Parent>();
+        world.register::<SetWorldTransform>();
+        world.register::<Reparent>();
+        world.register::<Transform2D>();
+        world.add_resource(ParentHierarchy::new());
 
         (world, TransformSystem::new())
     }
@@ -524,4 +820,247 @@ mod tests {
         assert_eq!(world.is_alive(e4), false);
         assert_eq!(world.is_alive(e5), false);
     }
+
+    #[test]
+    fn hierarchy() {
+        let (mut world, mut system) = transform_world();
+
+        let e1 = world.create_entity().with(LocalTransform::default()).build();
+        let e2 = world
+            .create_entity()
+            .with(LocalTransform::default())
+            .with(Parent { entity: e1 })
+            .build();
+        let e3 = world
+            .create_entity()
+            .with(LocalTransform::default())
+            .with(Parent { entity: e2 })
+            .build();
+
+        system.run_now(&mut world.res);
+        {
+            let hierarchy = world.read_resource::<ParentHierarchy>();
+            assert_eq!(hierarchy.parent(e1), None);
+            assert_eq!(hierarchy.parent(e3), Some(e2));
+            assert_eq!(hierarchy.children(e1), &[e2]);
+            assert_eq!(hierarchy.all_children(e1), vec![e2, e3]);
+        }
+
+        world.write::<Parent>().insert(e3, Parent { entity: e1 });
+        system.run_now(&mut world.res);
+        {
+            let hierarchy = world.read_resource::<ParentHierarchy>();
+            assert_eq!(hierarchy.children(e1), &[e2, e3]);
+            assert!(hierarchy.children(e2).is_empty());
+        }
+
+        world.write::<Parent>().remove(e2);
+        system.run_now(&mut world.res);
+        {
+            let hierarchy = world.read_resource::<ParentHierarchy>();
+            assert_eq!(hierarchy.parent(e2), None);
+            assert_eq!(hierarchy.children(e1), &[e3]);
+        }
+
+        world
+            .read_resource::<ParentHierarchy>()
+            .delete_recursive(&world.entities(), e1);
+        world.maintain();
+        assert!(world.is_alive(e2));
+        assert!(!world.is_alive(e3));
+    }
+
+    #[test]
+    fn orphan_policies() {
+        let (mut world, mut system) = transform_world();
+
+        let mut local = LocalTransform::default();
+        local.translation = Vector3::new(1.0, 2.0, 3.0);
+
+        let mut spawn = |world: &mut World| {
+            let parent = world
+                .create_entity()
+                .with(local.clone())
+                .with(Transform::default())
+                .build();
+            let child = world
+                .create_entity()
+                .with(local.clone())
+                .with(Transform::default())
+                .with(Parent { entity: parent })
+                .build();
+            (parent, child)
+        };
+
+        world
+            .write_resource::<ParentHierarchy>()
+            .set_orphan_policy(OrphanPolicy::Detach);
+        let (parent, child) = spawn(&mut world);
+        system.run_now(&mut world.res);
+        let _ = world.delete_entity(parent);
+        system.run_now(&mut world.res);
+        world.maintain();
+
+        assert!(world.is_alive(child));
+        assert_eq!(world.read::<Parent>().get(child), None);
+        let translation = world.read::<LocalTransform>().get(child).unwrap().translation;
+        assert_eq!(translation, Vector3::new(2.0, 4.0, 6.0));
+
+        world
+            .write_resource::<ParentHierarchy>()
+            .set_orphan_policy(OrphanPolicy::Keep);
+        let (parent, child) = spawn(&mut world);
+        system.run_now(&mut world.res);
+        let _ = world.delete_entity(parent);
+        system.run_now(&mut world.res);
+        world.maintain();
+
+        assert!(world.is_alive(child));
+        assert_eq!(world.read::<Parent>().get(child), Some(&Parent { entity: parent }));
+        assert_eq!(world.read_resource::<ParentHierarchy>().parent(child), None);
+        let global: [[f32; 4]; 4] = world.read::<Transform>().get(child).unwrap().clone().into();
+        let expected: [[f32; 4]; 4] = local.matrix().into();
+        assert_eq!(global, expected);
+    }
+
+    #[test]
+    fn without_hierarchy() {
+        let mut system = TransformSystem::new();
+        let mut world = World::new();
+        world.register::<LocalTransform>();
+        world.register::<Transform>();
+        world.register::<Parent>();
+
+        let mut local = LocalTransform::default();
+        local.translation = Vector3::new(1.0, 2.0, 3.0);
+        let parent = world
+            .create_entity()
+            .with(local.clone())
+            .with(Transform::default())
+            .build();
+        let child = world
+            .create_entity()
+            .with(local.clone())
+            .with(Transform::default())
+            .with(Parent { entity: parent })
+            .build();
+        system.run_now(&mut world.res);
+
+        let global: [[f32; 4]; 4] = world.read::<Transform>().get(child).unwrap().clone().into();
+        let expected: [[f32; 4]; 4] = (local.matrix() * local.matrix()).into();
+        assert_eq!(global, expected);
+
+        // Orphans are deleted, like with the default `OrphanPolicy`.
+        let _ = world.delete_entity(parent);
+        system.run_now(&mut world.res);
+        world.maintain();
+        assert!(!world.is_alive(child));
+    }
+
+    #[test]
+    fn world_space() {
+        let (mut world, mut system) = transform_world();
+
+        let mut local = LocalTransform::default();
+        local.translation = Vector3::new(1.0, 2.0, 3.0);
+        local.scale = Vector3::new(2.0, 2.0, 2.0);
+        let parent = world
+            .create_entity()
+            .with(local.clone())
+            .with(Transform::default())
+            .build();
+
+        let mut pose = LocalTransform::default();
+        pose.translation = Vector3::new(5.0, 5.0, 5.0);
+        let child = world
+            .create_entity()
+            .with(Transform::default())
+            .with(Parent { entity: parent })
+            .with(SetWorldTransform(pose))
+            .build();
+
+        system.run_now(&mut world.res);
+        {
+            let local = world.read::<LocalTransform>().get(child).unwrap().clone();
+            assert_eq!(local.translation, Vector3::new(2.0, 1.5, 1.0));
+            assert_eq!(local.scale, Vector3::new(0.5, 0.5, 0.5));
+            let global = world.read::<Transform>().get(child).unwrap().clone();
+            assert_eq!(global.translation(), Vector3::new(5.0, 5.0, 5.0));
+            assert_eq!(global.scale(), Vector3::new(1.0, 1.0, 1.0));
+            assert!(world.read::<SetWorldTransform>().get(child).is_none());
+        }
+
+        world.write::<Reparent>().insert(child, Reparent(None));
+        system.run_now(&mut world.res);
+
+        assert_eq!(world.read::<Parent>().get(child), None);
+        let local = world.read::<LocalTransform>().get(child).unwrap().clone();
+        assert_eq!(local.translation, Vector3::new(5.0, 5.0, 5.0));
+        let global = world.read::<Transform>().get(child).unwrap().clone();
+        assert_eq!(global.translation(), Vector3::new(5.0, 5.0, 5.0));
+    }
+
+    #[test]
+    fn reparent_to_child() {
+        let (mut world, mut system) = transform_world();
+
+        let parent = world
+            .create_entity()
+            .with(LocalTransform::default())
+            .with(Transform::default())
+            .build();
+        let child = world
+            .create_entity()
+            .with(LocalTransform::default())
+            .with(Transform::default())
+            .with(Parent { entity: parent })
+            .build();
+        let grandchild = world
+            .create_entity()
+            .with(LocalTransform::default())
+            .with(Transform::default())
+            .with(Parent { entity: child })
+            .build();
+        system.run_now(&mut world.res);
+
+        world.write::<Reparent>().insert(parent, Reparent(Some(grandchild)));
+        system.run_now(&mut world.res);
+
+        assert_eq!(world.read::<Parent>().get(parent), None);
+        assert!(world.read::<Reparent>().get(parent).is_none());
+        assert_eq!(world.read::<Parent>().get(child), Some(&Parent { entity: parent }));
+    }
+
+    #[test]
+    fn transform_2d() {
+        let (mut world, mut system) = transform_world();
+
+        let mut transform = Transform2D::default();
+        transform.position = Vector2::new(1.0, 2.0);
+        transform.layer = 3.0;
+        let parent = world
+            .create_entity()
+            .with(transform.clone())
+            .with(Transform::default())
+            .build();
+        let child = world
+            .create_entity()
+            .with(transform.clone())
+            .with(Transform::default())
+            .with(Parent { entity: parent })
+            .build();
+
+        system.run_now(&mut world.res);
+        assert_eq!(
+            world.read::<Transform>().get(child).unwrap().translation(),
+            Vector3::new(2.0, 4.0, 6.0)
+        );
+
+        world.write::<Transform2D>().get_mut(parent).unwrap().position = Vector2::new(0.0, 0.0);
+        system.run_now(&mut world.res);
+        assert_eq!(
+            world.read::<Transform>().get(child).unwrap().translation(),
+            Vector3::new(1.0, 2.0, 6.0)
+        );
+    }
 }
@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 
 use animation::{Animation, AnimationHierarchy, AnimationSet, Sampler};
 use assets::{AssetStorage, Handle, HotReloadStrategy, Loader};
-use core::{ThreadPool, Time};
+use core::{ErrorQueue, ThreadPool, Time};
 use core::transform::*;
 use fnv::FnvHashMap;
 use renderer::{Material, MaterialDefaults, Mesh, Texture};
@@ -45,6 +45,7 @@ impl<'a> System<'a> for GltfSceneLoaderSystem {
         Fetch<'a, Time>,
         Fetch<'a, ThreadPool>,
         Option<Fetch<'a, HotReloadStrategy>>,
+        Option<Fetch<'a, ErrorQueue>>,
         FetchMut<'a, AssetStorage<GltfSceneAsset>>,
         WriteStorage<'a, Handle<GltfSceneAsset>>,
         WriteStorage<'a, Handle<Mesh>>,
@@ -71,6 +72,7 @@ impl<'a> System<'a> for GltfSceneLoaderSystem {
             time,
             pool,
             strategy,
+            errors,
             mut scene_storage,
             mut scenes,
             mut meshes,
@@ -84,6 +86,7 @@ impl<'a> System<'a> for GltfSceneLoaderSystem {
 
         let strategy = strategy.as_ref().map(Deref::deref);
         scene_storage.process(Into::into, time.frame_number(), &**pool, strategy);
+        scene_storage.report_errors(errors.as_ref().map(Deref::deref));
 
         let mut deletes = vec![];
 
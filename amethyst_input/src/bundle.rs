@@ -4,14 +4,14 @@ use std::hash::Hash;
 use std::path::Path;
 
 use amethyst_config::Config;
-use amethyst_core::bundle::{ECSBundle, Result};
+use amethyst_core::bundle::{ECSBundle, RequiredResource, Result, SystemsBuilder};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use shrev::EventChannel;
-use specs::{DispatcherBuilder, World};
+use specs::{DispatcherBuilder, Fetch, SystemData, World};
 use winit::Event;
 
-use {Bindings, InputEvent, InputHandler, InputSystem};
+use {Bindings, InputEvent, InputHandler, InputSystem, RecordedEvent};
 
 /// Bundle for adding the `InputHandler`.
 ///
@@ -70,6 +70,15 @@ where
         world: &mut World,
         builder: DispatcherBuilder<'a, 'b>,
     ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
         let mut input = InputHandler::new();
         if let Some(bindings) = self.bindings {
             input.bindings = bindings;
@@ -78,13 +87,28 @@ where
         let reader_id = world
             .read_resource::<EventChannel<Event>>()
             .register_reader();
+        let mut system = InputSystem::<AX, AC>::new(reader_id);
+        // The channel of replayed sessions only exists when running in an `Application`.
+        let recorded_reader = Option::<Fetch<EventChannel<RecordedEvent>>>::fetch(&world.res, 0)
+            .map(|channel| channel.register_reader());
+        if let Some(recorded_reader) = recorded_reader {
+            system = system.with_recorded_reader(recorded_reader);
+        }
 
         world.add_resource(input);
         world.add_resource(EventChannel::<InputEvent<AC>>::with_capacity(2000));
-        Ok(builder.add(
-            InputSystem::<AX, AC>::new(reader_id),
-            "input_system",
-            &[],
-        ))
+        Ok(builder.add(system, "input_system", &[]))
+    }
+
+    fn name(&self) -> &str {
+        "input"
+    }
+
+    fn required_resources(&self) -> Vec<RequiredResource> {
+        vec![RequiredResource::new::<EventChannel<Event>>("EventChannel<Event>")]
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        vec!["input_system".to_owned()]
     }
 }
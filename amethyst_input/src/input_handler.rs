@@ -5,11 +5,12 @@ use std::hash::Hash;
 
 use shrev::EventChannel;
 use smallvec::SmallVec;
-use winit::{ElementState, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent};
+use winit::{MouseButton, VirtualKeyCode, WindowEvent};
 
 use super::*;
 use super::event::InputEvent;
 use super::event::InputEvent::*;
+use super::recording::{InputState, RecordedEvent};
 
 /// This struct holds state information about input devices.
 ///
@@ -54,19 +55,26 @@ where
         &mut self,
         event: &WindowEvent,
         event_handler: &mut EventChannel<InputEvent<AC>>,
+    ) {
+        self.send_recorded_event(&RecordedEvent::from_window_event(event), event_handler);
+    }
+
+    /// Updates the input handler with a recorded event.
+    ///
+    /// This is used instead of `send_event` when replaying a recorded session.
+    pub fn send_recorded_event(
+        &mut self,
+        event: &RecordedEvent,
+        event_handler: &mut EventChannel<InputEvent<AC>>,
     ) {
         match *event {
-            WindowEvent::ReceivedCharacter(c) => {
+            RecordedEvent::ReceivedCharacter(c) => {
                 event_handler.single_write(KeyTyped(c));
             }
-            WindowEvent::KeyboardInput {
-                input:
-                    KeyboardInput {
-                        state: ElementState::Pressed,
-                        virtual_keycode: Some(key_code),
-                        scancode,
-                        ..
-                    },
+            RecordedEvent::KeyboardInput {
+                key_code: Some(key_code),
+                scancode,
+                pressed: true,
                 ..
             } => if self.pressed_keys.iter().all(|&k| k.0 != key_code) {
                 self.pressed_keys.push((key_code, scancode));
@@ -88,14 +96,10 @@ where
                     }
                 }
             },
-            WindowEvent::KeyboardInput {
-                input:
-                    KeyboardInput {
-                        state: ElementState::Released,
-                        virtual_keycode: Some(key_code),
-                        scancode,
-                        ..
-                    },
+            RecordedEvent::KeyboardInput {
+                key_code: Some(key_code),
+                scancode,
+                pressed: false,
                 ..
             } => {
                 let index = self.pressed_keys.iter().position(|&k| k.0 == key_code);
@@ -120,10 +124,9 @@ where
                     }
                 }
             }
-            WindowEvent::MouseInput {
-                state: ElementState::Pressed,
+            RecordedEvent::MouseInput {
                 button,
-                ..
+                pressed: true,
             } => {
                 let mouse_button = button;
                 if self.pressed_mouse_buttons
@@ -146,10 +149,9 @@ where
                     }
                 }
             }
-            WindowEvent::MouseInput {
-                state: ElementState::Released,
+            RecordedEvent::MouseInput {
                 button,
-                ..
+                pressed: false,
             } => {
                 let mouse_button = button;
                 let index = self.pressed_mouse_buttons
@@ -172,9 +174,7 @@ where
                     }
                 }
             }
-            WindowEvent::MouseMoved {
-                position: (x, y), ..
-            } => {
+            RecordedEvent::MouseMoved { x, y } => {
                 if let Some((old_x, old_y)) = self.mouse_position {
                     event_handler.single_write(MouseMoved {
                         delta_x: x - old_x,
@@ -183,7 +183,7 @@ where
                 }
                 self.mouse_position = Some((x, y));
             }
-            WindowEvent::Focused(false) => {
+            RecordedEvent::Focused(false) => {
                 self.pressed_keys.clear();
                 self.pressed_mouse_buttons.clear();
                 self.mouse_position = None;
@@ -192,6 +192,14 @@ where
         }
     }
 
+    /// Takes a snapshot of the pressed buttons and the mouse position.
+    pub fn state(&self) -> InputState {
+        InputState {
+            buttons: self.buttons_that_are_down().collect(),
+            mouse_position: self.mouse_position,
+        }
+    }
+
     /// Returns an iterator over all keys that are down.
     pub fn keys_that_are_down(&self) -> KeyCodes {
         self.pressed_keys
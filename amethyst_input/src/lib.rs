@@ -17,6 +17,8 @@ pub use self::bundle::InputBundle;
 pub use self::button::Button;
 pub use self::event::InputEvent;
 pub use self::input_handler::InputHandler;
+pub use self::recording::{EventRebuilder, InputSource, InputState, RecordedEvent, RecordedFrame,
+                          Recording};
 pub use self::system::InputSystem;
 
 use std::iter::{Chain, FlatMap, Iterator, Map};
@@ -31,6 +33,7 @@ mod input_handler;
 mod local_mouse_button;
 mod local_virtual_key_code;
 mod event;
+mod recording;
 mod system;
 mod bundle;
 
@@ -0,0 +1,438 @@
+//! Serializable input for recording and replaying sessions.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use winit::{DeviceEvent, DeviceId, ElementState, Event, KeyboardInput, ModifiersState,
+            MouseButton, MouseScrollDelta, TouchPhase, VirtualKeyCode, WindowEvent, WindowId};
+
+use super::button::Button;
+use super::local_mouse_button::LocalMouseButton;
+
+/// A `winit` event, in a form that can be serialized.
+///
+/// Unlike `winit` events, these don't carry window or device ids, so they can be
+/// written to a file and fed back into a later session. The device events are prefixed
+/// with `Device`, all other variants are window events.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum RecordedEvent {
+    /// The window was resized.
+    Resized(u32, u32),
+    /// The window was moved.
+    Moved(i32, i32),
+    /// The window was closed.
+    Closed,
+    /// A file was dropped into the window.
+    DroppedFile(PathBuf),
+    /// A file is hovered over the window.
+    HoveredFile(PathBuf),
+    /// The hovered file left the window, or the hovering was cancelled.
+    HoveredFileCancelled,
+    /// A unicode character was received by the window.
+    ReceivedCharacter(char),
+    /// The window gained (`true`) or lost (`false`) focus.
+    Focused(bool),
+    /// A key was pressed or released.
+    KeyboardInput {
+        #[serde(with = "optional_key_code")]
+        key_code: Option<VirtualKeyCode>,
+        scancode: u32,
+        pressed: bool,
+        #[serde(with = "LocalModifiersState")]
+        modifiers: ModifiersState,
+    },
+    /// The mouse pointer moved to a new position in the window.
+    MouseMoved { x: f64, y: f64 },
+    /// The mouse pointer entered the window.
+    MouseEntered,
+    /// The mouse pointer left the window.
+    MouseLeft,
+    /// The mouse wheel was used.
+    MouseWheel {
+        #[serde(with = "LocalMouseScrollDelta")]
+        delta: MouseScrollDelta,
+        #[serde(with = "LocalTouchPhase")]
+        phase: TouchPhase,
+    },
+    /// A mouse button was pressed or released.
+    MouseInput {
+        #[serde(with = "LocalMouseButton")]
+        button: MouseButton,
+        pressed: bool,
+    },
+    /// The pressure on a touchpad changed.
+    TouchpadPressure { pressure: f32, stage: i64 },
+    /// An axis of a device moved while the window was focused.
+    AxisMotion { axis: u32, value: f64 },
+    /// The window needs to be redrawn.
+    Refresh,
+    /// The window was touched.
+    Touch {
+        #[serde(with = "LocalTouchPhase")]
+        phase: TouchPhase,
+        x: f64,
+        y: f64,
+        id: u64,
+    },
+    /// A device was added.
+    DeviceAdded,
+    /// A device was removed.
+    DeviceRemoved,
+    /// An axis of a device moved.
+    DeviceMotion { axis: u32, value: f64 },
+    /// A button of a device was pressed or released.
+    DeviceButton { button: u32, pressed: bool },
+    /// A key of a device was pressed or released.
+    DeviceKey {
+        #[serde(with = "optional_key_code")]
+        key_code: Option<VirtualKeyCode>,
+        scancode: u32,
+        pressed: bool,
+        #[serde(with = "LocalModifiersState")]
+        modifiers: ModifiersState,
+    },
+    /// A device sent a unicode character.
+    DeviceText(char),
+    /// The event loop was woken up.
+    Awakened,
+    /// The application was suspended (`true`) or resumed (`false`).
+    Suspended(bool),
+}
+
+impl RecordedEvent {
+    /// Converts a `winit` event, dropping its window or device id.
+    pub fn from_event(event: &Event) -> Self {
+        match *event {
+            Event::WindowEvent { ref event, .. } => RecordedEvent::from_window_event(event),
+            Event::DeviceEvent { ref event, .. } => RecordedEvent::from_device_event(event),
+            Event::Awakened => RecordedEvent::Awakened,
+            Event::Suspended(suspended) => RecordedEvent::Suspended(suspended),
+        }
+    }
+
+    /// Converts a window event, dropping its device id.
+    pub fn from_window_event(event: &WindowEvent) -> Self {
+        use self::RecordedEvent::*;
+
+        match *event {
+            WindowEvent::Resized(width, height) => Resized(width, height),
+            WindowEvent::Moved(x, y) => Moved(x, y),
+            WindowEvent::Closed => Closed,
+            WindowEvent::DroppedFile(ref path) => DroppedFile(path.clone()),
+            WindowEvent::HoveredFile(ref path) => HoveredFile(path.clone()),
+            WindowEvent::HoveredFileCancelled => HoveredFileCancelled,
+            WindowEvent::ReceivedCharacter(c) => ReceivedCharacter(c),
+            WindowEvent::Focused(focused) => Focused(focused),
+            WindowEvent::KeyboardInput { ref input, .. } => KeyboardInput {
+                key_code: input.virtual_keycode,
+                scancode: input.scancode,
+                pressed: input.state == ElementState::Pressed,
+                modifiers: input.modifiers,
+            },
+            WindowEvent::MouseMoved {
+                position: (x, y), ..
+            } => MouseMoved { x, y },
+            WindowEvent::MouseEntered { .. } => MouseEntered,
+            WindowEvent::MouseLeft { .. } => MouseLeft,
+            WindowEvent::MouseWheel { delta, phase, .. } => MouseWheel { delta, phase },
+            WindowEvent::MouseInput { state, button, .. } => MouseInput {
+                button,
+                pressed: state == ElementState::Pressed,
+            },
+            WindowEvent::TouchpadPressure {
+                pressure, stage, ..
+            } => TouchpadPressure { pressure, stage },
+            WindowEvent::AxisMotion { axis, value, .. } => AxisMotion { axis, value },
+            WindowEvent::Refresh => Refresh,
+            WindowEvent::Touch(ref touch) => Touch {
+                phase: touch.phase,
+                x: touch.location.0,
+                y: touch.location.1,
+                id: touch.id,
+            },
+        }
+    }
+
+    fn from_device_event(event: &DeviceEvent) -> Self {
+        use self::RecordedEvent::*;
+
+        match *event {
+            DeviceEvent::Added => DeviceAdded,
+            DeviceEvent::Removed => DeviceRemoved,
+            DeviceEvent::Motion { axis, value } => DeviceMotion { axis, value },
+            DeviceEvent::Button { button, state } => DeviceButton {
+                button,
+                pressed: state == ElementState::Pressed,
+            },
+            DeviceEvent::Key(ref input) => DeviceKey {
+                key_code: input.virtual_keycode,
+                scancode: input.scancode,
+                pressed: input.state == ElementState::Pressed,
+                modifiers: input.modifiers,
+            },
+            DeviceEvent::Text { codepoint } => DeviceText(codepoint),
+        }
+    }
+
+    /// Rebuilds the `winit` event, with the given window and device ids.
+    pub fn to_event(&self, window_id: WindowId, device_id: DeviceId) -> Event {
+        use self::RecordedEvent::*;
+
+        let window = |event| Event::WindowEvent { window_id, event };
+        let device = |event| Event::DeviceEvent { device_id, event };
+        let state = |pressed| if pressed {
+            ElementState::Pressed
+        } else {
+            ElementState::Released
+        };
+
+        match *self {
+            Resized(width, height) => window(WindowEvent::Resized(width, height)),
+            Moved(x, y) => window(WindowEvent::Moved(x, y)),
+            Closed => window(WindowEvent::Closed),
+            DroppedFile(ref path) => window(WindowEvent::DroppedFile(path.clone())),
+            HoveredFile(ref path) => window(WindowEvent::HoveredFile(path.clone())),
+            HoveredFileCancelled => window(WindowEvent::HoveredFileCancelled),
+            ReceivedCharacter(c) => window(WindowEvent::ReceivedCharacter(c)),
+            Focused(focused) => window(WindowEvent::Focused(focused)),
+            KeyboardInput {
+                key_code,
+                scancode,
+                pressed,
+                modifiers,
+            } => window(WindowEvent::KeyboardInput {
+                device_id,
+                input: keyboard_input(key_code, scancode, state(pressed), modifiers),
+            }),
+            MouseMoved { x, y } => window(WindowEvent::MouseMoved {
+                device_id,
+                position: (x, y),
+            }),
+            MouseEntered => window(WindowEvent::MouseEntered { device_id }),
+            MouseLeft => window(WindowEvent::MouseLeft { device_id }),
+            MouseWheel { delta, phase } => window(WindowEvent::MouseWheel {
+                device_id,
+                delta,
+                phase,
+            }),
+            MouseInput { button, pressed } => window(WindowEvent::MouseInput {
+                device_id,
+                state: state(pressed),
+                button,
+            }),
+            TouchpadPressure { pressure, stage } => window(WindowEvent::TouchpadPressure {
+                device_id,
+                pressure,
+                stage,
+            }),
+            AxisMotion { axis, value } => window(WindowEvent::AxisMotion {
+                device_id,
+                axis,
+                value,
+            }),
+            Refresh => window(WindowEvent::Refresh),
+            Touch { phase, x, y, id } => window(WindowEvent::Touch(::winit::Touch {
+                device_id,
+                phase,
+                location: (x, y),
+                id,
+            })),
+            DeviceAdded => device(DeviceEvent::Added),
+            DeviceRemoved => device(DeviceEvent::Removed),
+            DeviceMotion { axis, value } => device(DeviceEvent::Motion { axis, value }),
+            DeviceButton { button, pressed } => device(DeviceEvent::Button {
+                button,
+                state: state(pressed),
+            }),
+            DeviceKey {
+                key_code,
+                scancode,
+                pressed,
+                modifiers,
+            } => device(DeviceEvent::Key(keyboard_input(
+                key_code,
+                scancode,
+                state(pressed),
+                modifiers,
+            ))),
+            DeviceText(codepoint) => device(DeviceEvent::Text { codepoint }),
+            Awakened => Event::Awakened,
+            Suspended(suspended) => Event::Suspended(suspended),
+        }
+    }
+}
+
+fn keyboard_input(
+    virtual_keycode: Option<VirtualKeyCode>,
+    scancode: u32,
+    state: ElementState,
+    modifiers: ModifiersState,
+) -> KeyboardInput {
+    KeyboardInput {
+        scancode,
+        state,
+        virtual_keycode,
+        modifiers,
+    }
+}
+
+/// Rebuilds `winit` events from `RecordedEvent`s while replaying a session.
+///
+/// The replayed events get the window and device ids of the latest live events, so states
+/// comparing ids see the ids of the current window. Headless sessions have no live events,
+/// so only their `Awakened` and `Suspended` events can be rebuilt.
+#[derive(Debug, Default)]
+pub struct EventRebuilder {
+    window_id: Option<WindowId>,
+    device_id: Option<DeviceId>,
+}
+
+impl EventRebuilder {
+    /// Creates a new rebuilder, which hasn't seen any ids yet.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Remembers the window or device id of a live event.
+    pub fn observe(&mut self, event: &Event) {
+        match *event {
+            Event::WindowEvent {
+                window_id,
+                ref event,
+            } => {
+                self.window_id = Some(window_id);
+                if let Some(device_id) = window_event_device(event) {
+                    self.device_id = Some(device_id);
+                }
+            }
+            Event::DeviceEvent { device_id, .. } => self.device_id = Some(device_id),
+            _ => {}
+        }
+    }
+
+    /// Rebuilds the `winit` event of a recorded event.
+    ///
+    /// `winit` offers no way to create window or device ids, so window and device events
+    /// can only be rebuilt once a live event provided the ids. Until then `None` is returned
+    /// for them, they are still available as `RecordedEvent`s in their event channel.
+    pub fn rebuild(&self, event: &RecordedEvent) -> Option<Event> {
+        match *event {
+            RecordedEvent::Awakened => Some(Event::Awakened),
+            RecordedEvent::Suspended(suspended) => Some(Event::Suspended(suspended)),
+            _ => match (self.window_id, self.device_id) {
+                (Some(window_id), Some(device_id)) => Some(event.to_event(window_id, device_id)),
+                _ => None,
+            },
+        }
+    }
+}
+
+fn window_event_device(event: &WindowEvent) -> Option<DeviceId> {
+    match *event {
+        WindowEvent::KeyboardInput { device_id, .. }
+        | WindowEvent::MouseMoved { device_id, .. }
+        | WindowEvent::MouseEntered { device_id }
+        | WindowEvent::MouseLeft { device_id }
+        | WindowEvent::MouseWheel { device_id, .. }
+        | WindowEvent::MouseInput { device_id, .. }
+        | WindowEvent::TouchpadPressure { device_id, .. }
+        | WindowEvent::AxisMotion { device_id, .. } => Some(device_id),
+        WindowEvent::Touch(ref touch) => Some(touch.device_id),
+        _ => None,
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "ModifiersState")]
+struct LocalModifiersState {
+    shift: bool,
+    ctrl: bool,
+    alt: bool,
+    logo: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "MouseScrollDelta")]
+enum LocalMouseScrollDelta {
+    LineDelta(f32, f32),
+    PixelDelta(f32, f32),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "TouchPhase")]
+enum LocalTouchPhase {
+    Started,
+    Moved,
+    Ended,
+    Cancelled,
+}
+
+mod optional_key_code {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use winit::VirtualKeyCode;
+
+    use local_virtual_key_code::LocalVirtualKeyCode;
+
+    #[derive(Serialize, Deserialize)]
+    struct KeyCode(#[serde(with = "LocalVirtualKeyCode")] VirtualKeyCode);
+
+    pub fn serialize<S>(key_code: &Option<VirtualKeyCode>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        key_code.map(KeyCode).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<VirtualKeyCode>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<KeyCode>::deserialize(deserializer).map(|key_code| key_code.map(|k| k.0))
+    }
+}
+
+/// A snapshot of the state of an `InputHandler`.
+///
+/// Used to detect when a replayed session diverges from the recording.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct InputState {
+    /// All buttons that are down.
+    pub buttons: Vec<Button>,
+    /// The last known mouse position.
+    pub mouse_position: Option<(f64, f64)>,
+}
+
+/// Everything needed to replay a single frame.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RecordedFrame {
+    /// The real time that passed since the last frame.
+    pub delta: Duration,
+    /// The events received in this frame.
+    pub events: Vec<RecordedEvent>,
+    /// The state of the `InputHandler` at the end of the frame, if it was recorded.
+    pub input: Option<InputState>,
+}
+
+/// A recorded session, which can be saved as a RON file using `amethyst_config::Config`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Recording {
+    /// The recorded frames, in order.
+    pub frames: Vec<RecordedFrame>,
+}
+
+/// Where the `InputSystem` takes its input from.
+///
+/// If this resource is missing, input is read from the window.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InputSource {
+    /// Read `winit` events from `EventChannel<Event>`.
+    Window,
+    /// Read `RecordedEvent`s from `EventChannel<RecordedEvent>`, ignoring the window.
+    Recording,
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Window
+    }
+}
@@ -7,15 +7,20 @@ use shrev::{EventChannel, ReaderId};
 use specs::{Fetch, FetchMut, System};
 use winit::Event;
 
-use {InputEvent, InputHandler};
+use {InputEvent, InputHandler, InputSource, RecordedEvent};
 
 /// Input system
 ///
 /// Will read `winit::Event` from `EventHandler<winit::Event>`, process them with `InputHandler`,
 /// and push the results in `EventHandler<InputEvent>`.
+///
+/// If the `InputSource` resource is set to `InputSource::Recording`, the window events are
+/// ignored and `RecordedEvent`s are read from `EventHandler<RecordedEvent>` instead, see
+/// `with_recorded_reader`.
 pub struct InputSystem<AX, AC> {
     m: marker::PhantomData<(AX, AC)>,
     reader: ReaderId,
+    recorded_reader: Option<ReaderId>,
 }
 
 impl<AX, AC> InputSystem<AX, AC> {
@@ -24,9 +29,17 @@ impl<AX, AC> InputSystem<AX, AC> {
         Self {
             m: marker::PhantomData,
             reader,
+            recorded_reader: None,
         }
     }
 
+    /// Read replayed events from `EventHandler<RecordedEvent>` with the given reader id.
+    /// Without it, nothing is read while the `InputSource` is `InputSource::Recording`.
+    pub fn with_recorded_reader(mut self, recorded_reader: ReaderId) -> Self {
+        self.recorded_reader = Some(recorded_reader);
+        self
+    }
+
     fn process_event(
         event: &Event,
         handler: &mut InputHandler<AX, AC>,
@@ -48,16 +61,34 @@ where
 {
     type SystemData = (
         Fetch<'a, EventChannel<Event>>,
+        Option<Fetch<'a, EventChannel<RecordedEvent>>>,
+        Option<Fetch<'a, InputSource>>,
         FetchMut<'a, InputHandler<AX, AC>>,
         FetchMut<'a, EventChannel<InputEvent<AC>>>,
     );
 
-    fn run(&mut self, (input, mut handler, mut output): Self::SystemData) {
+    fn run(&mut self, (input, recorded, source, mut handler, mut output): Self::SystemData) {
+        let source = source.map(|s| *s).unwrap_or_default();
+
+        // Both channels are always read, so a reader doesn't fall behind
+        // while its source is inactive.
         match input.lossy_read(&mut self.reader) {
             Ok(data) => for d in data {
-                Self::process_event(d, &mut *handler, &mut *output);
+                if source == InputSource::Window {
+                    Self::process_event(d, &mut *handler, &mut *output);
+                }
             },
             _ => (),
         }
+        if let (Some(recorded), Some(reader)) = (recorded, self.recorded_reader.as_mut()) {
+            match recorded.lossy_read(reader) {
+                Ok(data) => for d in data {
+                    if source == InputSource::Recording {
+                        handler.send_recorded_event(d, &mut *output);
+                    }
+                },
+                _ => (),
+            }
+        }
     }
 }
@@ -0,0 +1,109 @@
+use animation::Sampler;
+use assets::{Result, ResultExt, SimpleFormat};
+
+use {Prefab, PrefabData};
+
+/// Loads a `Prefab` from a RON file, see `PrefabData` for the layout.
+#[derive(Clone, Debug)]
+pub struct PrefabFormat;
+
+impl SimpleFormat<Prefab> for PrefabFormat {
+    const NAME: &'static str = "PREFAB";
+
+    type Options = ();
+
+    fn import(&self, bytes: Vec<u8>, _: ()) -> Result<PrefabData> {
+        use ron::de::from_str;
+        use std::str::from_utf8;
+
+        let s = from_utf8(&bytes)?;
+        let data: PrefabData = from_str(s).chain_err(|| "Failed to decode prefab file")?;
+        validate(&data)?;
+
+        Ok(data)
+    }
+}
+
+/// Loads an animation `Sampler` from a RON file.
+#[derive(Clone, Debug)]
+pub struct SamplerFormat;
+
+impl SimpleFormat<Sampler> for SamplerFormat {
+    const NAME: &'static str = "SAMPLER";
+
+    type Options = ();
+
+    fn import(&self, bytes: Vec<u8>, _: ()) -> Result<Sampler> {
+        use ron::de::from_str;
+        use std::str::from_utf8;
+
+        let s = from_utf8(&bytes)?;
+
+        from_str(s).chain_err(|| "Failed to decode sampler file")
+    }
+}
+
+/// Checks that the prefab has a root and that all entity indices are valid.
+fn validate(data: &PrefabData) -> Result<()> {
+    match data.entities.first() {
+        None => return Err("Prefab has no entities".into()),
+        Some(root) if root.parent.is_some() => {
+            return Err("The prefab root can't have a parent".into())
+        }
+        _ => {}
+    }
+
+    for (index, entity) in data.entities.iter().enumerate() {
+        match entity.parent {
+            Some(parent) if parent >= index => {
+                return Err(format!(
+                    "Entity {} has parent {}, parents have to be listed before their children",
+                    index, parent
+                ).into())
+            }
+            _ => {}
+        }
+    }
+
+    for animation in &data.animations {
+        for &(index, ref path) in &animation.samplers {
+            if index >= data.entities.len() {
+                return Err(format!("Sampler {:?} animates missing entity {}", path, index).into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {PrefabAnimation, PrefabEntity};
+
+    #[test]
+    fn validate_indices() {
+        let mut data = PrefabData::default();
+        assert!(validate(&data).is_err());
+
+        data.entities.push(PrefabEntity::default());
+        data.entities.push(PrefabEntity {
+            parent: Some(0),
+            ..Default::default()
+        });
+        assert!(validate(&data).is_ok());
+
+        data.entities[0].parent = Some(1);
+        assert!(validate(&data).is_err());
+        data.entities[0].parent = None;
+
+        data.entities[1].parent = Some(1);
+        assert!(validate(&data).is_err());
+        data.entities[1].parent = None;
+
+        data.animations.push(PrefabAnimation {
+            samplers: vec![(2, "spin.ron".to_owned())],
+        });
+        assert!(validate(&data).is_err());
+    }
+}
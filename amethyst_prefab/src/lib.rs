@@ -0,0 +1,198 @@
+//! Prefabs, describing entity hierarchies in RON files.
+//!
+//! A `Prefab` is loaded through the `Loader` with the `PrefabFormat`. Attaching a
+//! `Handle<Prefab>` to an entity makes the `PrefabLoaderSystem` load all assets the
+//! prefab references, and then create the prefab's entities with that entity as root.
+//!
+//! The system requires the `AssetStorage<Prefab>` resource, the `Handle<Prefab>`
+//! component, and the resources and components of the `TransformBundle`,
+//! `RenderBundle` and `AnimationBundle`.
+
+#![warn(missing_docs)]
+
+extern crate amethyst_animation as animation;
+extern crate amethyst_assets as assets;
+extern crate amethyst_core as core;
+extern crate amethyst_renderer as renderer;
+extern crate fnv;
+extern crate ron;
+#[macro_use]
+extern crate serde;
+extern crate specs;
+
+pub use format::{PrefabFormat, SamplerFormat};
+pub use systems::PrefabLoaderSystem;
+
+use animation::Animation;
+use assets::{Asset, Error as AssetError, Handle, ProgressCounter};
+use core::transform::LocalTransform;
+use renderer::{Camera, Light, Material, MeshHandle};
+use specs::{DenseVecStorage, Entity, World};
+
+mod format;
+mod systems;
+
+/// The file format of a mesh.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MeshFormat {
+    /// Wavefront OBJ, see `ObjFormat`.
+    Obj,
+}
+
+/// A mesh file.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PrefabMesh {
+    /// The path of the file.
+    pub path: String,
+    /// The format of the file.
+    pub format: MeshFormat,
+}
+
+/// The file format of a texture.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum TextureFormat {
+    /// See `PngFormat`.
+    Png,
+    /// See `JpgFormat`.
+    Jpg,
+    /// See `BmpFormat`.
+    Bmp,
+}
+
+/// A texture of a `PrefabMaterial`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum PrefabTexture {
+    /// A texture file.
+    File {
+        /// The path of the file.
+        path: String,
+        /// The format of the file.
+        format: TextureFormat,
+    },
+    /// A single color.
+    Color([f32; 4]),
+}
+
+/// A material, missing textures are taken from the `MaterialDefaults`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct PrefabMaterial {
+    /// Diffuse map.
+    pub albedo: Option<PrefabTexture>,
+    /// Emission map.
+    pub emission: Option<PrefabTexture>,
+    /// Normal map.
+    pub normal: Option<PrefabTexture>,
+    /// Metallic map.
+    pub metallic: Option<PrefabTexture>,
+    /// Roughness map.
+    pub roughness: Option<PrefabTexture>,
+    /// Ambient occlusion map.
+    pub ambient_occlusion: Option<PrefabTexture>,
+    /// Caveat map.
+    pub caveat: Option<PrefabTexture>,
+}
+
+/// A single entity of a prefab.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct PrefabEntity {
+    /// The index of the parent entity in the prefab, which has to be listed before
+    /// this entity. Entities without a parent become children of the root.
+    pub parent: Option<usize>,
+    /// The local transform, defaults to the identity.
+    pub transform: Option<LocalTransform>,
+    /// A light source.
+    pub light: Option<Light>,
+    /// A camera.
+    pub camera: Option<Camera>,
+    /// A mesh, loaded from a file.
+    pub mesh: Option<PrefabMesh>,
+    /// A material, loaded from texture files.
+    pub material: Option<PrefabMaterial>,
+}
+
+/// An animation of a prefab.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct PrefabAnimation {
+    /// The animated entities, given by their index in the prefab, and the path of the
+    /// `Sampler` animating them, see `SamplerFormat`.
+    pub samplers: Vec<(usize, String)>,
+}
+
+/// The data of a prefab, as loaded from a RON file.
+///
+/// ```ron
+/// (
+///     entities: [
+///         (
+///             mesh: Some((path: "mesh/cube.obj", format: Obj)),
+///             material: Some((
+///                 albedo: Some(File(path: "texture/crate.png", format: Png)),
+///             )),
+///         ),
+///         (
+///             parent: Some(0),
+///             light: Some(Point((
+///                 center: (0.0, 2.0, 0.0),
+///                 color: (1.0, 1.0, 1.0, 1.0),
+///                 intensity: 3.0,
+///                 radius: 10.0,
+///                 smoothness: 4.0,
+///             ))),
+///         ),
+///     ],
+///     animations: [
+///         (samplers: [(0, "animation/spin.ron")]),
+///     ],
+/// )
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct PrefabData {
+    /// The entities of the prefab, the first one is the root.
+    pub entities: Vec<PrefabEntity>,
+    /// The animations of the prefab, they are added to the `AnimationSet` of the root.
+    pub animations: Vec<PrefabAnimation>,
+}
+
+/// A prefab asset.
+///
+/// The assets a prefab references are loaded the first time it is spawned, and
+/// reused for further instances.
+pub struct Prefab {
+    /// The data the prefab was loaded from.
+    pub data: PrefabData,
+    handles: Option<PrefabHandles>,
+}
+
+/// The assets loaded for a prefab.
+struct PrefabHandles {
+    progress: ProgressCounter,
+    meshes: Vec<Option<MeshHandle>>,
+    materials: Vec<Option<Material>>,
+    animations: Vec<Handle<Animation>>,
+}
+
+impl Into<Result<Prefab, AssetError>> for PrefabData {
+    fn into(self) -> Result<Prefab, AssetError> {
+        Ok(Prefab {
+            data: self,
+            handles: None,
+        })
+    }
+}
+
+impl Asset for Prefab {
+    type Data = PrefabData;
+    type HandleStorage = DenseVecStorage<Handle<Self>>;
+}
+
+/// Creates the root entity of a new instance of `prefab`.
+///
+/// The `PrefabLoaderSystem` adds the prefab's components and child entities to it
+/// once all assets of the prefab are loaded.
+pub fn spawn_prefab(world: &mut World, prefab: Handle<Prefab>) -> Entity {
+    world.create_entity().with(prefab).build()
+}
@@ -0,0 +1,306 @@
+use animation::{Animation, AnimationHierarchy, AnimationSet, Sampler};
+use assets::{AssetStorage, Completion, Handle, HotReloadStrategy, Loader, ProgressCounter};
+use core::{ErrorEvent, ErrorQueue, Severity, ThreadPool, Time};
+use core::transform::*;
+use fnv::FnvHashMap;
+use renderer::{BmpFormat, Camera, JpgFormat, Light, Material, MaterialDefaults, Mesh,
+               MeshHandle, ObjFormat, PngFormat, Texture, TextureHandle};
+use specs::{Entities, Fetch, FetchMut, Join, System, WriteStorage};
+
+use {MeshFormat, Prefab, PrefabData, PrefabHandles, PrefabMaterial, PrefabTexture, SamplerFormat,
+     TextureFormat};
+
+/// Spawns the entities of `Handle<Prefab>` components, using the entity holding the
+/// handle as root of the prefab. The handle is removed once the prefab is spawned.
+///
+/// Will also do the asset storage processing for `Prefab`.
+pub struct PrefabLoaderSystem {
+    _dummy: (),
+}
+
+impl PrefabLoaderSystem {
+    /// Creates a new prefab loader system.
+    pub fn new() -> Self {
+        Self { _dummy: () }
+    }
+}
+
+impl<'a> System<'a> for PrefabLoaderSystem {
+    type SystemData = (
+        Entities<'a>,
+        Fetch<'a, AssetStorage<Mesh>>,
+        Fetch<'a, AssetStorage<Texture>>,
+        Fetch<'a, AssetStorage<Animation>>,
+        Fetch<'a, AssetStorage<Sampler>>,
+        Fetch<'a, Loader>,
+        Fetch<'a, MaterialDefaults>,
+        Fetch<'a, Time>,
+        Fetch<'a, ThreadPool>,
+        Option<Fetch<'a, HotReloadStrategy>>,
+        Option<Fetch<'a, ErrorQueue>>,
+        FetchMut<'a, AssetStorage<Prefab>>,
+        WriteStorage<'a, Handle<Prefab>>,
+        WriteStorage<'a, MeshHandle>,
+        WriteStorage<'a, Material>,
+        WriteStorage<'a, Light>,
+        WriteStorage<'a, Camera>,
+        WriteStorage<'a, LocalTransform>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, Parent>,
+        WriteStorage<'a, AnimationHierarchy>,
+        WriteStorage<'a, AnimationSet>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        use std::ops::Deref;
+
+        let (
+            entities,
+            mesh_storage,
+            texture_storage,
+            animation_storage,
+            sampler_storage,
+            loader,
+            material_defaults,
+            time,
+            pool,
+            strategy,
+            errors,
+            mut prefab_storage,
+            mut prefabs,
+            mut meshes,
+            mut materials,
+            mut lights,
+            mut cameras,
+            mut local_transforms,
+            mut transforms,
+            mut parents,
+            mut animation_hierarchies,
+            mut animation_sets,
+        ) = data;
+
+        let strategy = strategy.as_ref().map(Deref::deref);
+        prefab_storage.process(Into::into, time.frame_number(), &**pool, strategy);
+        prefab_storage.report_errors(errors.as_ref().map(Deref::deref));
+
+        let mut spawned = vec![];
+
+        for (root, prefab_handle) in (&*entities, &prefabs).join() {
+            let prefab = match prefab_storage.get_mut(prefab_handle) {
+                Some(prefab) => prefab,
+                None => continue,
+            };
+
+            // The assets of a prefab are loaded once, and shared between its instances.
+            if prefab.handles.is_none() {
+                let loaders = AssetLoaders {
+                    loader: &loader,
+                    meshes: &mesh_storage,
+                    textures: &texture_storage,
+                    samplers: &sampler_storage,
+                    animations: &animation_storage,
+                    material_defaults: &material_defaults.0,
+                };
+                prefab.handles = Some(loaders.load(&prefab.data));
+            }
+
+            let data = &prefab.data;
+            let handles = prefab.handles.as_ref().unwrap();
+            match handles.progress.complete() {
+                Completion::Loading => continue,
+                Completion::Failed => if let Some(ref errors) = errors {
+                    errors.push(ErrorEvent::new(
+                        Severity::Warning,
+                        format!(
+                            "Spawning prefab with {} missing assets",
+                            handles.progress.num_failed()
+                        ),
+                    ));
+                },
+                Completion::Complete => {}
+            }
+
+            let mut created = Vec::with_capacity(data.entities.len());
+            for (index, prefab_entity) in data.entities.iter().enumerate() {
+                let entity = if index == 0 { root } else { entities.create() };
+                created.push(entity);
+
+                if index != 0 {
+                    let parent = created[prefab_entity.parent.unwrap_or(0)];
+                    parents.insert(entity, Parent { entity: parent });
+                }
+
+                match prefab_entity.transform {
+                    Some(ref local) => {
+                        local_transforms.insert(entity, local.clone());
+                    }
+                    None => if local_transforms.get(entity).is_none() {
+                        local_transforms.insert(entity, LocalTransform::default());
+                    },
+                }
+                if transforms.get(entity).is_none() {
+                    transforms.insert(entity, Transform::default());
+                }
+
+                if let Some(ref light) = prefab_entity.light {
+                    lights.insert(entity, light.clone());
+                }
+                if let Some(ref camera) = prefab_entity.camera {
+                    cameras.insert(entity, camera.clone());
+                }
+                if let Some(ref mesh) = handles.meshes[index] {
+                    meshes.insert(entity, mesh.clone());
+                }
+                if let Some(ref material) = handles.materials[index] {
+                    materials.insert(entity, material.clone());
+                }
+            }
+
+            if !handles.animations.is_empty() {
+                animation_hierarchies.insert(
+                    root,
+                    AnimationHierarchy {
+                        nodes: data.animations
+                            .iter()
+                            .flat_map(|animation| animation.samplers.iter())
+                            .map(|&(index, _)| (index, created[index]))
+                            .collect::<FnvHashMap<_, _>>(),
+                    },
+                );
+                animation_sets.insert(
+                    root,
+                    AnimationSet {
+                        animations: handles.animations.clone(),
+                    },
+                );
+            }
+
+            spawned.push(root);
+        }
+
+        for entity in spawned {
+            prefabs.remove(entity);
+        }
+    }
+}
+
+/// The storages needed to load the assets of a prefab.
+struct AssetLoaders<'a> {
+    loader: &'a Loader,
+    meshes: &'a AssetStorage<Mesh>,
+    textures: &'a AssetStorage<Texture>,
+    samplers: &'a AssetStorage<Sampler>,
+    animations: &'a AssetStorage<Animation>,
+    material_defaults: &'a Material,
+}
+
+impl<'a> AssetLoaders<'a> {
+    fn load(&self, data: &PrefabData) -> PrefabHandles {
+        let mut progress = ProgressCounter::new();
+
+        let meshes = data.entities
+            .iter()
+            .map(|entity| {
+                entity.mesh.as_ref().map(|mesh| match mesh.format {
+                    MeshFormat::Obj => self.loader.load(
+                        mesh.path.clone(),
+                        ObjFormat,
+                        (),
+                        &mut progress,
+                        self.meshes,
+                    ),
+                })
+            })
+            .collect();
+
+        let materials = data.entities
+            .iter()
+            .map(|entity| {
+                entity
+                    .material
+                    .as_ref()
+                    .map(|material| self.load_material(material, &mut progress))
+            })
+            .collect();
+
+        let animations = data.animations
+            .iter()
+            .map(|animation| {
+                let nodes = animation
+                    .samplers
+                    .iter()
+                    .map(|&(index, ref path)| {
+                        let sampler = self.loader.load(
+                            path.clone(),
+                            SamplerFormat,
+                            (),
+                            &mut progress,
+                            self.samplers,
+                        );
+                        (index, sampler)
+                    })
+                    .collect();
+
+                self.loader
+                    .load_from_data(Animation { nodes }, &mut progress, self.animations)
+            })
+            .collect();
+
+        PrefabHandles {
+            progress,
+            meshes,
+            materials,
+            animations,
+        }
+    }
+
+    fn load_material(&self, material: &PrefabMaterial, progress: &mut ProgressCounter) -> Material {
+        let defaults = self.material_defaults;
+        let mut load = |texture: &Option<PrefabTexture>, default: &TextureHandle| match *texture {
+            Some(ref texture) => self.load_texture(texture, progress),
+            None => default.clone(),
+        };
+
+        Material {
+            albedo: load(&material.albedo, &defaults.albedo),
+            emission: load(&material.emission, &defaults.emission),
+            normal: load(&material.normal, &defaults.normal),
+            metallic: load(&material.metallic, &defaults.metallic),
+            roughness: load(&material.roughness, &defaults.roughness),
+            ambient_occlusion: load(&material.ambient_occlusion, &defaults.ambient_occlusion),
+            caveat: load(&material.caveat, &defaults.caveat),
+        }
+    }
+
+    fn load_texture(&self, texture: &PrefabTexture, progress: &mut ProgressCounter) -> TextureHandle {
+        match *texture {
+            PrefabTexture::File { ref path, format } => match format {
+                TextureFormat::Png => self.loader.load(
+                    path.clone(),
+                    PngFormat,
+                    Default::default(),
+                    progress,
+                    self.textures,
+                ),
+                TextureFormat::Jpg => self.loader.load(
+                    path.clone(),
+                    JpgFormat,
+                    Default::default(),
+                    progress,
+                    self.textures,
+                ),
+                TextureFormat::Bmp => self.loader.load(
+                    path.clone(),
+                    BmpFormat,
+                    Default::default(),
+                    progress,
+                    self.textures,
+                ),
+            },
+            PrefabTexture::Color(color) => {
+                self.loader
+                    .load_from_data(color.into(), progress, self.textures)
+            }
+        }
+    }
+}
@@ -1,19 +1,23 @@
 //! ECS rendering bundle
 
-use amethyst_assets::{AssetStorage, Handle, Loader};
-use amethyst_core::bundle::{ECSBundle, Result};
+use amethyst_assets::{AssetStorage, Handle, HandleEntry, Loader};
+use amethyst_core::bundle::{ECSBundle, RequiredResource, Result, SystemsBuilder};
 use amethyst_core::orientation::Orientation;
+use amethyst_core::saveload::SaveRegistry;
 use amethyst_core::transform::components::*;
 use specs::{DispatcherBuilder, World};
 
-use {AmbientColor, Camera, Light, Material, MaterialDefaults, Mesh, Rgba, ScreenDimensions,
-     Texture, WindowMessages};
+use {AmbientColor, Camera, Light, Material, MaterialDefaults, Mesh, ObjFormat, PngFormat, Rgba,
+     ScreenDimensions, Texture, TextureMetadata, WindowMessages};
 
 /// Rendering bundle
 ///
 /// Will register all necessary components needed for rendering, along with any resources.
 /// Will also register asset contexts with the asset `Loader`, and add systems for merging
 /// `AssetFuture` into its related component.
+/// `Light` and `Camera` are added to the `SaveRegistry`, as well as mesh handles loaded as
+/// OBJ files and texture handles loaded as PNG files. Games using other formats can replace
+/// the `"MeshHandle"` and `"TextureHandle"` entries with `SaveRegistry::register_entry`.
 ///
 #[derive(Default)]
 pub struct RenderBundle;
@@ -31,6 +35,15 @@ impl<'a, 'b> ECSBundle<'a, 'b> for RenderBundle {
         world: &mut World,
         builder: DispatcherBuilder<'a, 'b>,
     ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
         world.add_resource(AmbientColor(Rgba::from([0.01; 3])));
         world.add_resource(WindowMessages::new());
         world.add_resource(ScreenDimensions::new(100, 100));
@@ -48,8 +61,28 @@ impl<'a, 'b> ECSBundle<'a, 'b> for RenderBundle {
         world.register::<Handle<Texture>>();
         world.register::<Camera>();
 
+        {
+            let mut registry = SaveRegistry::fetch_or_default(world);
+            registry.register::<Light>("Light");
+            registry.register::<Camera>("Camera");
+            registry.register_entry(HandleEntry::<Mesh, _>::new("MeshHandle", ObjFormat, ()));
+            registry.register_entry(HandleEntry::<Texture, _>::new(
+                "TextureHandle",
+                PngFormat,
+                TextureMetadata::default(),
+            ));
+        }
+
         Ok(builder)
     }
+
+    fn name(&self) -> &str {
+        "render"
+    }
+
+    fn required_resources(&self) -> Vec<RequiredResource> {
+        vec![RequiredResource::new::<Loader>("Loader")]
+    }
 }
 
 fn create_default_mat(world: &World) -> Material {
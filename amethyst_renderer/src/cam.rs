@@ -1,6 +1,8 @@
 //! Camera type with support for perspective and orthographic projections.
 
+use amethyst_core::bundle::Result;
 use amethyst_core::cgmath::{Deg, Matrix4, Ortho, PerspectiveFov};
+use amethyst_core::saveload::{LoadContext, SaveComponent, SaveContext};
 use specs::{Component, DenseVecStorage, Entity};
 
 /// The projection mode of a `Camera`.
@@ -91,6 +93,18 @@ impl Component for Camera {
     type Storage = DenseVecStorage<Self>;
 }
 
+impl SaveComponent for Camera {
+    type Data = Camera;
+
+    fn save(&self, _: &SaveContext) -> Self::Data {
+        self.clone()
+    }
+
+    fn load(data: Self::Data, _: &LoadContext) -> Result<Self> {
+        Ok(data)
+    }
+}
+
 /// Active camera resource, used by the renderer to choose which camera to get the view matrix from.
 /// If no active camera is found, the first camera will be used as a fallback.
 #[derive(Clone, Debug, PartialEq)]
@@ -22,6 +22,8 @@ extern crate gfx_core;
 extern crate gfx_macros;
 extern crate hetseq;
 extern crate imagefmt;
+#[macro_use]
+extern crate log;
 extern crate num_cpus;
 extern crate rayon;
 extern crate rayon_core;
@@ -2,6 +2,8 @@
 //!
 //! TODO: Remove redundant padding once `#[repr(align(...))]` stabilizes.
 
+use amethyst_core::bundle::Result;
+use amethyst_core::saveload::{LoadContext, SaveComponent, SaveContext};
 use gfx;
 use specs::{Component, DenseVecStorage};
 
@@ -178,3 +180,15 @@ impl From<SunLight> for Light {
 impl Component for Light {
     type Storage = DenseVecStorage<Self>;
 }
+
+impl SaveComponent for Light {
+    type Data = Light;
+
+    fn save(&self, _: &SaveContext) -> Self::Data {
+        self.clone()
+    }
+
+    fn load(data: Self::Data, _: &LoadContext) -> Result<Self> {
+        Ok(data)
+    }
+}
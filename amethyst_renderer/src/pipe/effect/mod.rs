@@ -77,8 +77,8 @@ impl Effect {
         match self.globals.get(name.as_ref()) {
             Some(i) => self.data.globals[*i] = data.convert(),
             None => {
-                eprintln!(
-                    "WARNING: Global update for effect failed! Global not found: {:?}",
+                warn!(
+                    "Global update for effect failed! Global not found: {:?}",
                     name.as_ref()
                 );
             }
@@ -98,8 +98,8 @@ impl Effect {
                     .expect("Failed to update buffer (TODO: replace expect)");
             }
             None => {
-                eprintln!(
-                    "WARNING: Buffer update for effect failed! Buffer not found: {:?}",
+                warn!(
+                    "Buffer update for effect failed! Buffer not found: {:?}",
                     name.as_ref()
                 );
             }
@@ -118,8 +118,8 @@ impl Effect {
                 enc.update_constant_buffer::<T>(unsafe { mem::transmute(raw) }, &data)
             }
             None => {
-                eprintln!(
-                    "WARNING: Buffer update for effect failed! Buffer not found: {:?}",
+                warn!(
+                    "Buffer update for effect failed! Buffer not found: {:?}",
                     name.as_ref()
                 );
             }
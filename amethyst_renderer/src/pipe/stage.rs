@@ -217,7 +217,7 @@ where
                 self.passes.new_target(target);
             }
             None => {
-                eprintln!("Target name {:?} not found!", self.target_name);
+                warn!("Target name {:?} not found!", self.target_name);
             }
         }
     }
@@ -5,7 +5,7 @@ use std::mem;
 use std::sync::Arc;
 
 use amethyst_assets::{AssetStorage, HotReloadStrategy};
-use amethyst_core::Time;
+use amethyst_core::{ErrorQueue, Time};
 use rayon::ThreadPool;
 use shred::Resources;
 use shrev::EventChannel;
@@ -68,7 +68,7 @@ where
 
     fn asset_loading(
         &mut self,
-        (time, pool, strategy, mut mesh_storage, mut texture_storage): AssetLoadingData,
+        (time, pool, strategy, mut mesh_storage, mut texture_storage, errors): AssetLoadingData,
     ) {
         use std::ops::Deref;
 
@@ -87,6 +87,9 @@ where
             &**pool,
             strategy,
         );
+
+        mesh_storage.report_errors(errors.as_ref().map(Deref::deref));
+        texture_storage.report_errors(errors.as_ref().map(Deref::deref));
     }
 
     fn window_management(&mut self, (mut window_messages, mut screen_dimensions): WindowData) {
@@ -132,10 +135,7 @@ where
         });
 
         if let Err(err) = event_handler.slice_write(&events) {
-            eprintln!(
-                "WARNING: Writing too many window events this frame! {:?}",
-                err
-            );
+            warn!("Writing too many window events this frame! {:?}", err);
         }
     }
 }
@@ -146,6 +146,7 @@ type AssetLoadingData<'a> = (
     Option<Fetch<'a, HotReloadStrategy>>,
     FetchMut<'a, AssetStorage<Mesh>>,
     FetchMut<'a, AssetStorage<Texture>>,
+    Option<Fetch<'a, ErrorQueue>>,
 );
 
 type WindowData<'a> = (FetchMut<'a, WindowMessages>, FetchMut<'a, ScreenDimensions>);
@@ -0,0 +1,170 @@
+//! Test support for Amethyst.
+//!
+//! Assembles headless applications, so systems can be tested by running
+//! a fixed number of frames and asserting on the `World` afterwards.
+//!
+//! ~~~no_run
+//! extern crate amethyst;
+//! extern crate amethyst_test;
+//!
+//! use amethyst::core::transform::{LocalTransform, Transform};
+//! use amethyst_test::TestApplication;
+//!
+//! # fn main() {
+//! let mut app = TestApplication::new()
+//!     .expect("Failed to initialize")
+//!     .build()
+//!     .expect("Failed to create Application");
+//!
+//! let entity = app.world
+//!     .create_entity()
+//!     .with(LocalTransform::default())
+//!     .with(Transform::default())
+//!     .build();
+//! app.run_frames(1);
+//!
+//! assert!(app.world.read::<Transform>().get(entity).is_some());
+//! # }
+//! ~~~
+
+#![warn(missing_docs)]
+
+extern crate amethyst;
+
+use std::time::Duration;
+
+use amethyst::{Application, ApplicationBuilder, Result, State};
+use amethyst::assets::{Asset, Processor, Result as AssetResult};
+use amethyst::core::ECSBundle;
+use amethyst::core::frame_limiter::FrameRateLimitStrategy;
+use amethyst::core::transform::TransformBundle;
+use amethyst::ecs::{Component, System};
+use amethyst::shred::Resource;
+
+/// A state which never stops the application on its own.
+#[derive(Debug, Default)]
+pub struct EmptyState;
+
+impl State for EmptyState {}
+
+/// Builds a headless `Application` for tests.
+///
+/// The application has the `TransformBundle` and advances `Time` by
+/// 1/60 of a second every frame, without waiting for the frame limiter.
+/// Use `Application::step` or `Application::run_frames` to run it.
+pub struct TestApplication<'a, 'b> {
+    builder: ApplicationBuilder<'a, 'b, EmptyState>,
+    processors: usize,
+}
+
+impl<'a, 'b> TestApplication<'a, 'b> {
+    /// Creates a new test application, loading assets from the current directory.
+    pub fn new() -> Result<Self> {
+        let builder = Application::build("./", EmptyState)?
+            .headless()
+            .with_frame_limit(FrameRateLimitStrategy::Unlimited, 0)
+            .with_fixed_delta_time(Duration::new(0, 16_666_667))
+            .with_bundle(TransformBundle::new())?;
+
+        Ok(TestApplication {
+            builder,
+            processors: 0,
+        })
+    }
+
+    /// Registers a component.
+    pub fn register<C>(mut self) -> Self
+    where
+        C: Component,
+    {
+        self.builder = self.builder.register::<C>();
+        self
+    }
+
+    /// Adds a resource to the world.
+    pub fn with_resource<R>(mut self, resource: R) -> Self
+    where
+        R: Resource,
+    {
+        self.builder = self.builder.with_resource(resource);
+        self
+    }
+
+    /// Adds the asset storage and a `Processor` for the asset type `A`.
+    pub fn with_asset<A>(mut self) -> Self
+    where
+        A: Asset,
+        A::Data: Into<AssetResult<A>>,
+    {
+        let name = format!("test_processor_{}", self.processors);
+        self.processors += 1;
+        self.builder = self.builder
+            .register_asset::<A>()
+            .with(Processor::<A>::new(), &name, &[]);
+        self
+    }
+
+    /// Adds a system to the dispatcher.
+    pub fn with<S>(mut self, system: S, name: &str, dependencies: &[&str]) -> Self
+    where
+        for<'c> S: System<'c> + Send + 'a,
+    {
+        self.builder = self.builder.with(system, name, dependencies);
+        self
+    }
+
+    /// Adds a bundle to the dispatcher.
+    pub fn with_bundle<B>(mut self, bundle: B) -> Result<Self>
+    where
+        B: ECSBundle<'a, 'b>,
+    {
+        self.builder = self.builder.with_bundle(bundle)?;
+        Ok(self)
+    }
+
+    /// Changes the delta time of every frame.
+    pub fn with_delta_time(mut self, delta: Duration) -> Self {
+        self.builder = self.builder.with_fixed_delta_time(delta);
+        self
+    }
+
+    /// Builds the application.
+    pub fn build(self) -> Result<Application<'a, 'b>> {
+        self.builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use amethyst::core::Time;
+    use amethyst::core::cgmath::Vector3;
+    use amethyst::core::transform::{LocalTransform, Parent, Transform};
+
+    use super::*;
+
+    #[test]
+    fn transform_hierarchy() {
+        let mut app = TestApplication::new().unwrap().build().unwrap();
+
+        let mut local = LocalTransform::default();
+        local.translation = Vector3::new(1.0, 2.0, 3.0);
+        let parent = app.world
+            .create_entity()
+            .with(local.clone())
+            .with(Transform::default())
+            .build();
+        let child = app.world
+            .create_entity()
+            .with(local)
+            .with(Transform::default())
+            .with(Parent { entity: parent })
+            .build();
+
+        assert!(app.run_frames(2));
+
+        let transforms = app.world.read::<Transform>();
+        let translation = transforms.get(child).unwrap().0.w.truncate();
+        assert_eq!(translation, Vector3::new(2.0, 4.0, 6.0));
+        assert_eq!(app.world.read_resource::<Time>().frame_number(), 2);
+    }
+}
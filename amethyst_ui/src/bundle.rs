@@ -1,7 +1,9 @@
 //! ECS rendering bundle
 
-use amethyst_assets::{AssetStorage, Handle, Processor};
-use amethyst_core::bundle::{ECSBundle, Result};
+use amethyst_assets::{AssetStorage, Handle, HandleEntry, Processor};
+use amethyst_core::bundle::{ECSBundle, RequiredResource, Result, SystemsBuilder};
+use amethyst_core::saveload::SaveRegistry;
+use amethyst_renderer::Texture;
 use shrev::EventChannel;
 use specs::{DispatcherBuilder, World};
 use winit::Event;
@@ -11,39 +13,79 @@ use super::*;
 /// UI bundle
 ///
 /// Will register all necessary components and systems needed for UI, along with any resources.
+/// Requires the `RenderBundle` to be added first.
 ///
 /// `UiTextRenderer` is registered with name `"ui_text"`.
-pub struct UiBundle {
-    deps: &'static [&'static str],
+/// Font handles are added to the `SaveRegistry` as `"FontHandle"`, loaded as TTF files.
+#[derive(Default)]
+pub struct UiBundle<'a> {
+    dep: &'a [&'a str],
 }
 
-impl UiBundle {
-    /// Create a new UI bundle, the dependencies given will be the dependencies for the
-    /// UiTextRenderer system.
-    pub fn new(deps: &'static [&'static str]) -> Self {
-        UiBundle {
-            deps
-        }
+impl<'a> UiBundle<'a> {
+    /// Create a new UI bundle
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set dependencies for the `UiTextRenderer` system
+    pub fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
+        self.dep = dep;
+        self
     }
 }
 
-impl<'a, 'b> ECSBundle<'a, 'b> for UiBundle {
+impl<'a, 'b, 'c> ECSBundle<'a, 'b> for UiBundle<'c> {
     fn build(
         self,
         world: &mut World,
         builder: DispatcherBuilder<'a, 'b>,
     ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
         world.register::<UiImage>();
         world.register::<UiTransform>();
         world.register::<UiText>();
         world.register::<UiResize>();
         world.register::<Handle<FontAsset>>();
         world.add_resource(AssetStorage::<FontAsset>::new());
+        SaveRegistry::fetch_or_default(world)
+            .register_entry(HandleEntry::<FontAsset, _>::new("FontHandle", TtfFormat, ()));
         let reader = world.read_resource::<EventChannel<Event>>().register_reader();
         Ok(builder
-            .add(UiTextRenderer, "ui_text", self.deps)
+            .add(UiTextRenderer, "ui_text", self.dep)
             .add(Processor::<FontAsset>::new(), "font_processor", &[])
             .add(ResizeSystem::new(reader), "ui_resize_system", &[])
         )
     }
+
+    fn name(&self) -> &str {
+        "ui"
+    }
+
+    fn required_resources(&self) -> Vec<RequiredResource> {
+        vec![
+            RequiredResource::new::<EventChannel<Event>>("EventChannel<Event>"),
+            RequiredResource::new::<AssetStorage<Texture>>("AssetStorage<Texture>"),
+        ]
+    }
+
+    fn required_systems(&self) -> Vec<String> {
+        self.dep.iter().map(|dep| dep.to_string()).collect()
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        vec![
+            "ui_text".to_owned(),
+            "font_processor".to_owned(),
+            "ui_resize_system".to_owned(),
+        ]
+    }
 }
@@ -1,8 +1,12 @@
 //! Provides components and systems to create an in game user interface.
+//!
+//! Tweens for `UiTransform` and `UiText` are available with the `tween` feature.
 
 #![deny(missing_docs)]
 #![doc(html_logo_url = "https://tinyurl.com/jtmm43a")]
 
+#[cfg(feature = "tween")]
+extern crate amethyst_animation;
 extern crate amethyst_assets;
 extern crate amethyst_core;
 extern crate amethyst_renderer;
@@ -23,6 +27,8 @@ mod pass;
 mod resize;
 mod text;
 mod transform;
+#[cfg(feature = "tween")]
+mod tween;
 
 pub use self::bundle::UiBundle;
 pub use self::format::{FontAsset, FontHandle, OtfFormat, TtfFormat};
@@ -31,3 +37,5 @@ pub use self::pass::DrawUi;
 pub use self::resize::{UiResize, ResizeSystem};
 pub use self::text::{UiText, UiTextRenderer};
 pub use self::transform::UiTransform;
+#[cfg(feature = "tween")]
+pub use self::tween::{UiTextTween, UiTransformTween};
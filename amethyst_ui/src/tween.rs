@@ -0,0 +1,76 @@
+//! Tweening of UI elements.
+
+use amethyst_animation::Tweenable;
+
+use text::UiText;
+use transform::UiTransform;
+
+/// The tweenable properties of `UiTransform`, with their start and end values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UiTransformTween {
+    /// Tweens `x` and `y`.
+    Position([f32; 2], [f32; 2]),
+    /// Tweens `width` and `height`.
+    Size([f32; 2], [f32; 2]),
+}
+
+impl Tweenable for UiTransform {
+    type Property = UiTransformTween;
+
+    fn tween(&mut self, property: &UiTransformTween, t: f32) {
+        match *property {
+            UiTransformTween::Position(start, end) => {
+                self.x = lerp(start[0], end[0], t);
+                self.y = lerp(start[1], end[1], t);
+            }
+            UiTransformTween::Size(start, end) => {
+                self.width = lerp(start[0], end[0], t);
+                self.height = lerp(start[1], end[1], t);
+            }
+        }
+    }
+}
+
+/// The tweenable properties of `UiText`, with their start and end values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UiTextTween {
+    /// Tweens the RGBA color, the channels are clamped to `[0, 1]`.
+    Color([f32; 4], [f32; 4]),
+}
+
+impl Tweenable for UiText {
+    type Property = UiTextTween;
+
+    fn tween(&mut self, property: &UiTextTween, t: f32) {
+        match *property {
+            UiTextTween::Color(start, end) => {
+                let mut color = [0.; 4];
+                for (channel, (start, end)) in color.iter_mut().zip(start.iter().zip(&end)) {
+                    *channel = lerp(*start, *end, t).max(0.).min(1.);
+                }
+                self.set_color(color);
+            }
+        }
+    }
+}
+
+fn lerp(start: f32, end: f32, t: f32) -> f32 {
+    start + (end - start) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ui_transform() {
+        let mut transform = UiTransform::new("button".to_string(), 0., 0., 1., 10., 10.);
+
+        transform.tween(&UiTransformTween::Position([0., 0.], [10., 20.]), 0.5);
+        assert_eq!((transform.x, transform.y), (5., 10.));
+
+        transform.tween(&UiTransformTween::Size([10., 10.], [20., 30.]), 0.25);
+        assert_eq!((transform.width, transform.height), (12.5, 15.));
+        assert_eq!((transform.x, transform.y, transform.z), (5., 10., 1.));
+    }
+}
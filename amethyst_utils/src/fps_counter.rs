@@ -1,6 +1,6 @@
 //! Util Resources
 
-use amethyst_core::{ECSBundle, Result};
+use amethyst_core::{ECSBundle, Result, SystemsBuilder};
 use amethyst_core::timing::{duration_to_nanos, Time};
 use circular_buffer::CircularBuffer;
 use specs::{DispatcherBuilder, Fetch, FetchMut, System, World};
@@ -58,7 +58,7 @@ pub struct FPSCounterSystem;
 impl<'a> System<'a> for FPSCounterSystem {
     type SystemData = (Fetch<'a, Time>, FetchMut<'a, FPSCounter>);
     fn run(&mut self, (time, mut counter): Self::SystemData) {
-        counter.push(duration_to_nanos(time.delta_time()));
+        counter.push(duration_to_nanos(time.delta_real_time()));
         //Enable this to debug performance engine wide.
         //println!("Cur FPS: {}, Sampled: {}",counter.frame_fps(),counter.sampled_fps());
     }
@@ -91,7 +91,24 @@ impl<'a, 'b> ECSBundle<'a, 'b> for FPSCounterBundle {
         world: &mut World,
         builder: DispatcherBuilder<'a, 'b>,
     ) -> Result<DispatcherBuilder<'a, 'b>> {
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        builder: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
         world.add_resource(FPSCounter::new(self.samplesize));
         Ok(builder.add(FPSCounterSystem, "fps_counter_system", &[]))
     }
+
+    fn name(&self) -> &str {
+        "fps_counter"
+    }
+
+    fn provided_systems(&self) -> Vec<String> {
+        vec!["fps_counter_system".to_owned()]
+    }
 }
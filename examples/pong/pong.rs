@@ -2,8 +2,8 @@ use {ARENA_HEIGHT, ARENA_WIDTH};
 use {Ball, Paddle, Side};
 use systems::ScoreText;
 use amethyst::assets::Loader;
-use amethyst::core::cgmath::Vector3;
-use amethyst::core::transform::{LocalTransform, Transform};
+use amethyst::core::cgmath::Vector2;
+use amethyst::core::transform::{Transform, Transform2D};
 use amethyst::ecs::World;
 use amethyst::prelude::*;
 use amethyst::renderer::{Camera, Event, KeyboardInput, Material, MeshHandle, PosTex, Projection,
@@ -77,13 +77,13 @@ fn hide_cursor(world: &mut World) {
 fn initialise_paddles(world: &mut World) {
     use {PADDLE_COLOUR, PADDLE_HEIGHT, PADDLE_VELOCITY, PADDLE_WIDTH};
 
-    let mut left_transform = LocalTransform::default();
-    let mut right_transform = LocalTransform::default();
+    let mut left_transform = Transform2D::default();
+    let mut right_transform = Transform2D::default();
 
     // Correctly position the paddles.
     let y = (ARENA_HEIGHT - PADDLE_HEIGHT) / 2.0;
-    left_transform.translation = Vector3::new(0.0, y, 0.0);
-    right_transform.translation = Vector3::new(ARENA_WIDTH - PADDLE_WIDTH, y, 0.0);
+    left_transform.position = Vector2::new(0.0, y);
+    right_transform.position = Vector2::new(ARENA_WIDTH - PADDLE_WIDTH, y);
 
     // Create the mesh and the material needed.
     let mesh = create_mesh(
@@ -131,8 +131,8 @@ fn initialise_balls(world: &mut World) {
     // Create the mesh, material and translation.
     let mesh = create_mesh(world, generate_circle_vertices(BALL_RADIUS, 16));
     let material = create_colour_material(world, BALL_COLOUR);
-    let mut local_transform = LocalTransform::default();
-    local_transform.translation = Vector3::new(ARENA_WIDTH / 2.0, ARENA_HEIGHT / 2.0, 0.0);
+    let mut local_transform = Transform2D::default();
+    local_transform.position = Vector2::new(ARENA_WIDTH / 2.0, ARENA_HEIGHT / 2.0);
 
     world
         .create_entity()
@@ -2,7 +2,7 @@ use {Ball, Paddle, Side};
 use amethyst::assets::AssetStorage;
 use amethyst::audio::Source;
 use amethyst::audio::output::Output;
-use amethyst::core::transform::LocalTransform;
+use amethyst::core::transform::Transform2D;
 use amethyst::ecs::{Fetch, Join, ReadStorage, System, WriteStorage};
 use audio::{play_bounce, Sounds};
 
@@ -14,7 +14,7 @@ impl<'s> System<'s> for BounceSystem {
     type SystemData = (
         WriteStorage<'s, Ball>,
         ReadStorage<'s, Paddle>,
-        ReadStorage<'s, LocalTransform>,
+        ReadStorage<'s, Transform2D>,
         Fetch<'s, AssetStorage<Source>>,
         Fetch<'s, Sounds>,
         Fetch<'s, Option<Output>>,
@@ -31,8 +31,8 @@ impl<'s> System<'s> for BounceSystem {
         for (ball, transform) in (&mut balls, &transforms).join() {
             use ARENA_HEIGHT;
 
-            let ball_x = transform.translation[0];
-            let ball_y = transform.translation[1];
+            let ball_x = transform.position[0];
+            let ball_y = transform.position[1];
 
             // Bounce at the top or the bottom of the arena.
             if ball_y <= ball.radius && ball.velocity[1] < 0.0 {
@@ -45,8 +45,8 @@ impl<'s> System<'s> for BounceSystem {
 
             // Bounce at the paddles.
             for (paddle, paddle_transform) in (&paddles, &transforms).join() {
-                let paddle_x = paddle_transform.translation[0];
-                let paddle_y = paddle_transform.translation[1];
+                let paddle_x = paddle_transform.position[0];
+                let paddle_y = paddle_transform.position[1];
 
                 // To determine whether the ball has collided with a paddle, we create a larger
                 // rectangle around the current one, by subtracting the ball radius from the
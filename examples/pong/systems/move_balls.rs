@@ -1,6 +1,6 @@
 use Ball;
 use amethyst::core::timing::Time;
-use amethyst::core::transform::LocalTransform;
+use amethyst::core::transform::Transform2D;
 use amethyst::ecs::{Fetch, Join, System, WriteStorage};
 
 /// This system is responsible for moving all balls according to their speed
@@ -10,15 +10,15 @@ pub struct MoveBallsSystem;
 impl<'s> System<'s> for MoveBallsSystem {
     type SystemData = (
         WriteStorage<'s, Ball>,
-        WriteStorage<'s, LocalTransform>,
+        WriteStorage<'s, Transform2D>,
         Fetch<'s, Time>,
     );
 
     fn run(&mut self, (mut balls, mut locals, time): Self::SystemData) {
         // Move every ball according to its speed, and the time passed.
         for (ball, local) in (&mut balls, &mut locals).join() {
-            local.translation[0] += ball.velocity[0] * time.delta_seconds();
-            local.translation[1] += ball.velocity[1] * time.delta_seconds();
+            local.position[0] += ball.velocity[0] * time.delta_seconds();
+            local.position[1] += ball.velocity[1] * time.delta_seconds();
         }
     }
 }
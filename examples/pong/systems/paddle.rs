@@ -1,6 +1,6 @@
 use Paddle;
 use amethyst::core::timing::Time;
-use amethyst::core::transform::LocalTransform;
+use amethyst::core::transform::Transform2D;
 use amethyst::ecs::{Fetch, Join, System, WriteStorage};
 use amethyst::input::InputHandler;
 
@@ -11,7 +11,7 @@ pub struct PaddleSystem;
 impl<'s> System<'s> for PaddleSystem {
     type SystemData = (
         WriteStorage<'s, Paddle>,
-        WriteStorage<'s, LocalTransform>,
+        WriteStorage<'s, Transform2D>,
         Fetch<'s, Time>,
         Fetch<'s, InputHandler<String, String>>,
     );
@@ -29,11 +29,11 @@ impl<'s> System<'s> for PaddleSystem {
 
             if let Some(movement) = opt_movement {
                 use ARENA_HEIGHT;
-                transform.translation[1] +=
+                transform.position[1] +=
                     paddle.velocity * time.delta_seconds() * movement as f32;
 
                 // We make sure the paddle remains in the arena.
-                transform.translation[1] = transform.translation[1]
+                transform.position[1] = transform.position[1]
                     .max(0.0)
                     .min(ARENA_HEIGHT - paddle.height);
             }
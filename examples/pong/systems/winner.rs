@@ -2,7 +2,7 @@ use {Ball, ScoreBoard};
 use amethyst::assets::AssetStorage;
 use amethyst::audio::Source;
 use amethyst::audio::output::Output;
-use amethyst::core::transform::LocalTransform;
+use amethyst::core::transform::Transform2D;
 use amethyst::ecs::{Entity, Fetch, FetchMut, Join, System, WriteStorage};
 use amethyst::ui::UiText;
 use audio::Sounds;
@@ -15,7 +15,7 @@ pub struct WinnerSystem;
 impl<'s> System<'s> for WinnerSystem {
     type SystemData = (
         WriteStorage<'s, Ball>,
-        WriteStorage<'s, LocalTransform>,
+        WriteStorage<'s, Transform2D>,
         WriteStorage<'s, UiText>,
         FetchMut<'s, ScoreBoard>,
         Fetch<'s, AssetStorage<Source>>,
@@ -32,7 +32,7 @@ impl<'s> System<'s> for WinnerSystem {
         for (ball, transform) in (&mut balls, &mut transforms).join() {
             use ARENA_WIDTH;
 
-            let ball_x = transform.translation[0];
+            let ball_x = transform.position[0];
 
             let did_hit = if ball_x <= ball.radius {
                 // Right player scored on the left side.
@@ -55,7 +55,7 @@ impl<'s> System<'s> for WinnerSystem {
             if did_hit {
                 // Reset the ball.
                 ball.velocity[0] = -ball.velocity[0];
-                transform.translation[0] = ARENA_WIDTH / 2.0;
+                transform.position[0] = ARENA_WIDTH / 2.0;
 
                 // Print the score board.
                 println!(
@@ -117,7 +117,7 @@ fn run() -> Result<(), amethyst::Error> {
 
     let mut game = Application::build(resources, Example)?
         .with_bundle(RenderBundle::new())?
-        .with_bundle(UiBundle::new(&[]))?;
+        .with_bundle(UiBundle::new())?;
     let pipe = {
         let loader = game.world.read_resource();
         let mesh_storage = game.world.read_resource();
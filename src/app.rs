@@ -1,10 +1,13 @@
 //! The core engine framework.
 
-use std::path::Path;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use core::ECSBundle;
+use core::{ECSBundle, ErrorEvent, ErrorQueue, FrameProfile, Profiled, ProfiledLocal, Severity,
+           SpanKind, SystemsBuilder, ThreadingConfig};
 use rayon::ThreadPool;
 use shred::{Resource, RunNow};
 use shrev::{EventChannel, ReaderId};
@@ -13,11 +16,18 @@ use thread_profiler::{register_thread_with_profiler, write_profile};
 use winit::{Event, WindowEvent};
 
 use assets::{Asset, Loader, Source};
+use config::Config;
+use config_reload::ConfigReloadSystem;
+use core::bundle::{check_provided_systems, check_requirements};
 use core::frame_limiter::{FrameLimiter, FrameRateLimitConfig, FrameRateLimitStrategy};
 use core::timing::{Stopwatch, Time};
-use ecs::{Component, Dispatcher, DispatcherBuilder, System, World};
+use ecs::{Component, Dispatcher, System, World};
 use ecs::common::Errors;
 use error::{Error, Result};
+use input::{EventRebuilder, InputHandler, InputSource, InputState, RecordedEvent, RecordedFrame,
+            Recording};
+use logger::{start_logger, LoggerConfig};
+use manifest::{BuiltinBundle, GameManifest};
 use state::{State, StateMachine};
 use vergen;
 
@@ -28,19 +38,30 @@ use vergen;
 /// to use any global variables. Within this object is everything that your
 /// game needs to run.
 #[derive(Derivative)]
-#[derivative(Debug)]
-pub struct Application<'a, 'b> {
+#[derivative(Debug(bound = ""))]
+pub struct Application<'a, 'b, E = ()> {
     /// The world
     #[derivative(Debug = "ignore")]
     pub world: World,
 
     #[derivative(Debug = "ignore")]
     dispatcher: Dispatcher<'a, 'b>,
-    events_reader_id: ReaderId,
-    states: StateMachine<'a>,
+    #[derivative(Debug = "ignore")]
+    fixed_dispatcher: Dispatcher<'a, 'b>,
+    events_reader_id: Option<ReaderId>,
+    errors_reader_id: ReaderId,
+    states: StateMachine<'a, E>,
+    #[derivative(Debug = "ignore")]
+    event_forwarders: Vec<Box<EventForwarder<E> + 'b>>,
     #[derivative(Debug = "ignore")]
     locals: Vec<Box<for<'c> RunNow<'c> + 'b>>,
     ignore_window_close: bool,
+    session: Session,
+    rebuilder: EventRebuilder,
+    #[derivative(Debug = "ignore")]
+    input_state: Option<fn(&World) -> InputState>,
+    fixed_delta_time: Option<Duration>,
+    initialized: bool,
 }
 
 impl<'a, 'b> Application<'a, 'b> {
@@ -112,7 +133,9 @@ impl<'a, 'b> Application<'a, 'b> {
     {
         ApplicationBuilder::new(path, initial_state)
     }
+}
 
+impl<'a, 'b, E> Application<'a, 'b, E> {
     /// Run the gameloop until the game state indicates that the game is no
     /// longer running. This is done via the `State` returning `Trans::Quit` or
     /// `Trans::Pop` on the last state in from the stack. See full
@@ -123,51 +146,169 @@ impl<'a, 'b> Application<'a, 'b> {
     /// See the example supplied in the
     /// [`new`](struct.Application.html#examples) method.
     pub fn run(&mut self) {
-        self.initialize();
-        self.world.write_resource::<Stopwatch>().start();
-        while self.states.is_running() {
-            self.advance_frame();
+        while self.step() {}
+    }
 
-            self.world.write_resource::<FrameLimiter>().wait();
-            {
-                let elapsed = self.world.read_resource::<Stopwatch>().elapsed();
-                let mut time = self.world.write_resource::<Time>();
-                time.increment_frame_number();
-                time.set_delta_time(elapsed);
+    /// Runs a single frame of the gameloop.
+    ///
+    /// The first call starts the initial state. Once the game state indicates that the game
+    /// is no longer running, the application is shut down and further calls do nothing.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the application is still running after this frame.
+    ///
+    /// # Examples
+    ///
+    /// ~~~no_run
+    /// use amethyst::prelude::*;
+    /// use amethyst::core::Time;
+    ///
+    /// struct NullState;
+    /// impl State for NullState {}
+    ///
+    /// let mut game = Application::build("assets/", NullState)
+    ///     .expect("Failed to initialize")
+    ///     .headless()
+    ///     .build()
+    ///     .expect("Failed to create Application");
+    ///
+    /// while game.step() {
+    ///     if game.world.read_resource::<Time>().frame_number() == 10 {
+    ///         break;
+    ///     }
+    /// }
+    /// ~~~
+    pub fn step(&mut self) -> bool {
+        if !self.initialized {
+            self.initialize();
+        }
+        if !self.states.is_running() {
+            return false;
+        }
+
+        self.advance_frame();
+        self.finish_frame();
+
+        if self.states.is_running() {
+            true
+        } else {
+            self.shutdown();
+            false
+        }
+    }
+
+    /// Runs `frames` frames of the gameloop, or less if the game stops running before.
+    ///
+    /// This is useful for tests, which can assert on the `World` afterwards.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the application is still running.
+    pub fn run_frames(&mut self, frames: u64) -> bool {
+        for _ in 0..frames {
+            if !self.step() {
+                return false;
             }
-            let mut stopwatch = self.world.write_resource::<Stopwatch>();
-            stopwatch.stop();
-            stopwatch.restart();
         }
 
-        self.shutdown();
+        self.states.is_running()
     }
 
     /// Sets up the application.
     fn initialize(&mut self) {
         #[cfg(feature = "profiler")]
         profile_scope!("initialize");
+        self.initialized = true;
         self.states.start(&mut self.world);
+        self.world.write_resource::<Stopwatch>().start();
+    }
+
+    /// Waits for the frame limiter and advances `Time` to the next frame.
+    fn finish_frame(&mut self) {
+        self.world.write_resource::<FrameLimiter>().wait();
+        {
+            let elapsed = self.world.read_resource::<Stopwatch>().elapsed();
+            let elapsed = self.session
+                .next_delta()
+                .or(self.fixed_delta_time)
+                .unwrap_or(elapsed);
+            let mut time = self.world.write_resource::<Time>();
+            time.increment_frame_number();
+            time.set_delta_time(elapsed);
+            time.advance_absolute_time();
+        }
+        let mut stopwatch = self.world.write_resource::<Stopwatch>();
+        stopwatch.stop();
+        stopwatch.restart();
+    }
+
+    /// Checks whether the application runs without a window.
+    ///
+    /// See [`ApplicationBuilder::headless`](struct.ApplicationBuilder.html#method.headless).
+    pub fn is_headless(&self) -> bool {
+        self.events_reader_id.is_none()
+    }
+
+    /// Checks whether the application replays a recorded session.
+    ///
+    /// See [`ApplicationBuilder::with_replay`](struct.ApplicationBuilder.html#method.with_replay).
+    pub fn is_replaying(&self) -> bool {
+        match self.session {
+            Session::Replay { .. } => true,
+            _ => false,
+        }
     }
 
     /// Advances the game world by one tick.
     fn advance_frame(&mut self) {
-        {
+        self.world.write_resource::<FrameProfile>().begin_frame();
+        let start = Instant::now();
+        let replayed = match self.session {
+            Session::Replay {
+                ref recording,
+                ref mut next,
+            } => match recording.frames.get(*next) {
+                Some(frame) => {
+                    *next += 1;
+                    Some(frame.clone())
+                }
+                None => {
+                    // The recording is empty.
+                    self.states.stop(&mut self.world);
+                    return;
+                }
+            },
+            _ => None,
+        };
+        let mut recorded = RecordedFrame {
+            delta: self.world.read_resource::<Time>().delta_real_time(),
+            ..RecordedFrame::default()
+        };
+
+        if let Some(ref mut events_reader_id) = self.events_reader_id {
             let world = &mut self.world;
             let states = &mut self.states;
+            let rebuilder = &mut self.rebuilder;
             #[cfg(feature = "profiler")]
             profile_scope!("handle_event");
 
             let events = match world
                 .read_resource::<EventChannel<Event>>()
-                .lossy_read(&mut self.events_reader_id)
+                .lossy_read(events_reader_id)
             {
                 Ok(data) => data.cloned().collect(),
                 _ => Vec::default(),
             };
 
             for event in events {
-                states.handle_event(world, event.clone());
+                recorded.events.push(RecordedEvent::from_event(&event));
+                rebuilder.observe(&event);
+                // While replaying, live events are replaced by the recorded ones, but the
+                // window can still be closed.
+                if replayed.is_none() {
+                    states.handle_event(world, event.clone());
+                }
                 if !self.ignore_window_close {
                     if let &Event::WindowEvent {
                         event: WindowEvent::Closed,
@@ -179,49 +320,251 @@ impl<'a, 'b> Application<'a, 'b> {
                 }
             }
         }
+        if let Some(ref frame) = replayed {
+            #[cfg(feature = "profiler")]
+            profile_scope!("replay_events");
+
+            {
+                let mut channel = self.world.write_resource::<EventChannel<RecordedEvent>>();
+                for event in &frame.events {
+                    channel.single_write(event.clone());
+                }
+            }
+            for event in &frame.events {
+                if let Some(rebuilt) = self.rebuilder.rebuild(event) {
+                    self.states.handle_event(&mut self.world, rebuilt);
+                }
+                if !self.ignore_window_close && *event == RecordedEvent::Closed {
+                    self.states.stop(&mut self.world);
+                }
+            }
+        }
+        self.record_phase("handle_event", start);
+        {
+            #[cfg(feature = "profiler")]
+            profile_scope!("handle_custom_event");
+            let start = Instant::now();
+
+            let mut events = Vec::new();
+            for forwarder in &mut self.event_forwarders {
+                forwarder.forward(&self.world, &mut events);
+            }
+
+            for event in events {
+                self.states.handle_custom_event(&mut self.world, event);
+            }
+            self.record_phase("handle_custom_event", start);
+        }
         {
-            let do_fixed = {
-                let time = self.world.write_resource::<Time>();
-                time.last_fixed_update().elapsed() >= time.fixed_time()
-            };
             #[cfg(feature = "profiler")]
             profile_scope!("fixed_update");
-            if do_fixed {
+            let start = Instant::now();
+            self.world.write_resource::<Time>().accumulate_fixed_time();
+            while self.world.write_resource::<Time>().step_fixed_update() {
                 self.states.fixed_update(&mut self.world);
-                self.world.write_resource::<Time>().finish_fixed_update();
+                self.fixed_dispatcher.dispatch(&mut self.world.res);
             }
+            self.record_phase("fixed_update", start);
 
             #[cfg(feature = "profiler")]
             profile_scope!("update");
+            let start = Instant::now();
             self.states.update(&mut self.world);
+            self.record_phase("update", start);
         }
 
         #[cfg(feature = "profiler")]
         profile_scope!("dispatch");
+        let start = Instant::now();
         self.dispatcher.dispatch(&mut self.world.res);
+        self.record_phase("dispatch", start);
 
+        let start = Instant::now();
         for local in &mut self.locals {
             local.run_now(&self.world.res);
         }
+        self.record_phase("render", start);
 
         #[cfg(feature = "profiler")]
         profile_scope!("maintain");
+        let start = Instant::now();
         self.world.maintain();
+        self.record_phase("maintain", start);
+
+        {
+            #[cfg(feature = "profiler")]
+            profile_scope!("record");
+
+            let input = self.input_state.map(|f| f(&self.world));
+            match self.session {
+                Session::Record(ref mut file) => {
+                    recorded.input = input;
+                    file.recording.frames.push(recorded);
+                }
+                Session::Replay {
+                    ref recording,
+                    next,
+                } => {
+                    let expected = replayed.and_then(|frame| frame.input);
+                    if let (Some(input), Some(expected)) = (input, expected) {
+                        if input != expected {
+                            let message = format!(
+                                "Replay diverged from the recording in frame {}: \
+                                 expected input {:?}, got {:?}",
+                                next - 1,
+                                expected,
+                                input,
+                            );
+                            self.world
+                                .write_resource::<EventChannel<ErrorEvent>>()
+                                .single_write(ErrorEvent::new(Severity::Warning, message));
+                        }
+                    }
+                    if next == recording.frames.len() {
+                        self.states.stop(&mut self.world);
+                    }
+                }
+                Session::Live => {}
+            }
+        }
+
+        #[cfg(feature = "profiler")]
+        profile_scope!("handle_errors");
+        let start = Instant::now();
+        self.handle_errors();
+        self.record_phase("handle_errors", start);
 
-        // TODO: replace this with a more customizable method.
-        // TODO: effectively, the user should have more control over error handling here
-        // TODO: because right now the app will just exit in case of an error.
-        self.world.write_resource::<Errors>().print_and_exit();
+        let frame_number = self.world.read_resource::<Time>().frame_number();
+        self.world
+            .write_resource::<FrameProfile>()
+            .end_frame(frame_number);
+    }
+
+    /// Records the timing of a phase of the current frame in the `FrameProfile`.
+    fn record_phase(&self, name: &str, start: Instant) {
+        self.world
+            .read_resource::<FrameProfile>()
+            .record(name, SpanKind::Phase, start);
+    }
+
+    /// Passes the errors reported this frame to the active state.
+    ///
+    /// Errors collected in the `Errors` and `ErrorQueue` resources are moved to the
+    /// `EventChannel<ErrorEvent>` first, so systems can report errors either way.
+    fn handle_errors(&mut self) {
+        {
+            let errors = self.world.write_resource::<Errors>().collect();
+            let queued = self.world.read_resource::<ErrorQueue>().drain();
+            let mut channel = self.world.write_resource::<EventChannel<ErrorEvent>>();
+            for error in errors {
+                channel.single_write(ErrorEvent::new(Severity::Error, format!("{}", error)));
+            }
+            for error in queued {
+                channel.single_write(error);
+            }
+        }
+
+        let errors = match self.world
+            .read_resource::<EventChannel<ErrorEvent>>()
+            .lossy_read(&mut self.errors_reader_id)
+        {
+            Ok(data) => data.cloned().collect(),
+            _ => Vec::default(),
+        };
+
+        for error in errors {
+            match error.severity {
+                Severity::Warning => warn!("{}", error),
+                _ => error!("{}", error),
+            }
+            self.states.handle_error(&mut self.world, error);
+        }
     }
 
     /// Cleans up after the quit signal is received.
     fn shutdown(&mut self) {
-        // Placeholder.
+        if let Session::Record(ref mut file) = self.session {
+            file.save();
+        }
     }
 }
 
+/// Whether a session is played live, recorded or replayed.
+#[derive(Debug)]
+enum Session {
+    Live,
+    Record(RecordingFile),
+    Replay { recording: Recording, next: usize },
+}
+
+/// A session being recorded to `path`.
+///
+/// The recording is saved at shutdown, and when it is dropped with unsaved frames, so a
+/// session ending in a panic is kept as well.
+#[derive(Debug)]
+struct RecordingFile {
+    path: PathBuf,
+    recording: Recording,
+    saved: usize,
+}
+
+impl RecordingFile {
+    fn new(path: PathBuf) -> Self {
+        RecordingFile {
+            path,
+            recording: Recording::default(),
+            saved: 0,
+        }
+    }
+
+    fn save(&mut self) {
+        self.saved = self.recording.frames.len();
+        if let Err(e) = self.recording.write(&self.path) {
+            error!("Failed to save recording to {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+impl Drop for RecordingFile {
+    fn drop(&mut self) {
+        if self.saved != self.recording.frames.len() {
+            self.save();
+        }
+    }
+}
+
+impl Session {
+    /// Returns the recorded delta time for the next replayed frame.
+    ///
+    /// Once the recording is over, no more time passes.
+    fn next_delta(&self) -> Option<Duration> {
+        match *self {
+            Session::Replay {
+                ref recording,
+                next,
+            } => Some(
+                recording
+                    .frames
+                    .get(next)
+                    .map(|frame| frame.delta)
+                    .unwrap_or_default(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Takes a snapshot of the `InputHandler<AX, AC>` resource.
+fn input_state<AX, AC>(world: &World) -> InputState
+where
+    AX: Hash + Eq + Clone + Send + Sync + 'static,
+    AC: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    world.read_resource::<InputHandler<AX, AC>>().state()
+}
+
 #[cfg(feature = "profiler")]
-impl<'a, 'b> Drop for Application<'a, 'b> {
+impl<'a, 'b, E> Drop for Application<'a, 'b, E> {
     fn drop(&mut self) {
         // TODO: Specify filename in config.
         let path = format!("{}/thread_profile.json", env!("CARGO_MANIFEST_DIR"));
@@ -232,17 +575,23 @@ impl<'a, 'b> Drop for Application<'a, 'b> {
 /// `ApplicationBuilder` is an interface that allows for creation of an [`Application`](struct.Application.html)
 /// using a custom set of configuration. This is the normal way an [`Application`](struct.Application.html)
 /// object is created.
-pub struct ApplicationBuilder<'a, 'b, T> {
+pub struct ApplicationBuilder<'a, 'b, T, E = ()> {
     // config: Config,
-    disp_builder: DispatcherBuilder<'a, 'b>,
+    disp_builder: SystemsBuilder<'a, 'b>,
+    fixed_disp_builder: SystemsBuilder<'a, 'b>,
     initial_state: T,
     /// Used by bundles to access the world directly
     pub world: World,
+    event_forwarders: Vec<Box<EventForwarder<E> + 'b>>,
     locals: Vec<Box<for<'c> RunNow<'c> + 'b>>,
     ignore_window_close: bool,
+    headless: bool,
+    session: Session,
+    input_state: Option<fn(&World) -> InputState>,
+    fixed_delta_time: Option<Duration>,
 }
 
-impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
+impl<'a, 'b, T, E> ApplicationBuilder<'a, 'b, T, E> {
     /// Creates a new [ApplicationBuilder](struct.ApplicationBuilder.html) instance
     /// that wraps the initial_state. This is the more verbose way of initializing
     /// your application if you require specific configuration details to be changed
@@ -278,7 +627,7 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
     ///
     /// ~~~no_run
     /// use amethyst::prelude::*;
-    /// use amethyst::core::transform::{Parent, LocalTransform, TransformSystem};
+    /// use amethyst::core::transform::{LocalTransform, Parent, Transform, TransformSystem};
     ///
     /// struct NullState;
     /// impl State for NullState {}
@@ -293,6 +642,7 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
     /// // components can be registered at this stage
     ///     .register::<Parent>()
     ///     .register::<LocalTransform>()
+    ///     .register::<Transform>()
     ///
     /// // systems can be added before the game is run
     ///     .with::<TransformSystem>(TransformSystem::new(), "transform_system", &[])
@@ -306,26 +656,212 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
     /// ~~~
 
     pub fn new<P: AsRef<Path>>(path: P, initial_state: T) -> Result<Self> {
+        Self::new_with_threading(path, initial_state, ThreadingConfig::default())
+    }
+
+    /// Creates a new [ApplicationBuilder](struct.ApplicationBuilder.html) instance
+    /// like [new](struct.ApplicationBuilder.html#method.new), but with a custom thread
+    /// pool configuration.
+    ///
+    /// By default, a single thread pool with one thread per logical core is used for
+    /// system dispatch and asset loading. The configuration can limit the number of
+    /// threads, name them, set their stack size, and add a separate pool for the asset
+    /// `Loader`, so loading assets doesn't compete with system dispatch.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The default path for asset loading.
+    /// - `initial_state`: The initial State handler of your game.
+    /// - `threading`: The thread pool configuration, e.g. loaded from a RON file.
+    ///
+    /// A logger with the default `LoggerConfig` is started, unless one was set before.
+    ///
+    /// # Errors
+    ///
+    /// Application will return an error if a thread pool fails to initialize.
+    ///
+    /// # Examples
+    ///
+    /// ~~~no_run
+    /// use amethyst::prelude::*;
+    /// use amethyst::config::Config;
+    /// use amethyst::core::ThreadingConfig;
+    ///
+    /// struct NullState;
+    /// impl State for NullState {}
+    ///
+    /// let threading = ThreadingConfig::load("resources/threading.ron");
+    /// let mut game = ApplicationBuilder::new_with_threading("assets/", NullState, threading)
+    ///     .expect("Failed to initialize")
+    ///     .build()
+    ///     .expect("Failed to create Application");
+    ///
+    /// game.run();
+    /// ~~~
+    pub fn new_with_threading<P: AsRef<Path>>(
+        path: P,
+        initial_state: T,
+        threading: ThreadingConfig,
+    ) -> Result<Self> {
+        // A logger set up by the game takes precedence.
+        let _ = start_logger(LoggerConfig::default());
+
+        Self::new_without_logger(path, initial_state, threading)
+    }
+
+    /// Creates the builder like `new_with_threading`, leaving the logger to the caller.
+    fn new_without_logger<P: AsRef<Path>>(
+        path: P,
+        initial_state: T,
+        threading: ThreadingConfig,
+    ) -> Result<Self> {
         use bundle::AppBundle;
 
-        println!("Initializing Amethyst...");
-        println!("Version: {}", vergen::semver());
-        println!("Platform: {}", vergen::target());
-        println!("Git commit: {}", vergen::sha());
+        info!("Initializing Amethyst...");
+        info!("Version: {}", vergen::semver());
+        info!("Platform: {}", vergen::target());
+        info!("Git commit: {}", vergen::sha());
 
-        let mut disp_builder = DispatcherBuilder::new();
         let mut world = World::new();
-        disp_builder = AppBundle::new(path).build(&mut world, disp_builder)?;
+        let disp_builder = AppBundle::new(path)
+            .with_threading(threading)
+            .build_systems(&mut world, SystemsBuilder::default())?;
 
         Ok(ApplicationBuilder {
             disp_builder,
+            fixed_disp_builder: SystemsBuilder::default(),
             initial_state,
             world,
+            event_forwarders: Vec::default(),
             locals: Vec::default(),
             ignore_window_close: false,
+            headless: false,
+            session: Session::Live,
+            input_state: None,
+            fixed_delta_time: None,
         })
     }
 
+    /// Creates a new [ApplicationBuilder](struct.ApplicationBuilder.html) set up by the
+    /// [GameManifest](struct.GameManifest.html) in a RON file.
+    ///
+    /// Relative paths in the manifest are relative to the directory of the manifest file.
+    ///
+    /// # Parameters
+    ///
+    /// - `path`: The path of the manifest file.
+    /// - `initial_state`: The initial State handler of your game.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest can't be loaded, its logger can't be started
+    /// or one of its bundles fails to build.
+    ///
+    /// # Examples
+    ///
+    /// ~~~no_run
+    /// use amethyst::prelude::*;
+    /// use amethyst::renderer::{DisplayConfig, DrawFlat, Pipeline, PosTex, RenderSystem, Stage};
+    ///
+    /// struct NullState;
+    /// impl State for NullState {}
+    ///
+    /// let game = ApplicationBuilder::from_manifest("game.ron", NullState)
+    ///     .expect("Failed to load manifest");
+    ///
+    /// let pipe = Pipeline::build().with_stage(
+    ///     Stage::with_backbuffer()
+    ///         .clear_target([0.0, 0.0, 0.0, 1.0], 1.0)
+    ///         .with_pass(DrawFlat::<PosTex>::new()),
+    /// );
+    /// let display = game.world.read_resource::<DisplayConfig>().clone();
+    /// let render = RenderSystem::build(pipe, Some(display)).expect("Failed to create renderer");
+    ///
+    /// game.with_local(render).build().expect("Failed to build game").run();
+    /// ~~~
+    pub fn from_manifest<P: AsRef<Path>>(path: P, initial_state: T) -> Result<Self> {
+        let path = path.as_ref();
+        let mut manifest = GameManifest::load_no_fallback(path).map_err(Error::Config)?;
+        if let Some(dir) = path.parent() {
+            manifest.resolve_paths(dir);
+        }
+
+        Self::from_game_manifest(manifest, initial_state)
+    }
+
+    /// Creates a new [ApplicationBuilder](struct.ApplicationBuilder.html) set up by
+    /// the given manifest. Relative paths are used as they are.
+    ///
+    /// See [from_manifest](struct.ApplicationBuilder.html#method.from_manifest).
+    pub fn from_game_manifest(manifest: GameManifest, initial_state: T) -> Result<Self> {
+        use assets::{Directory, HotReloadBundle};
+        use core::transform::TransformBundle;
+        use input::InputBundle;
+        use renderer::RenderBundle;
+        use ui::UiBundle;
+
+        match manifest.logger {
+            Some(logger) => start_logger(logger)?,
+            // A logger set up by the game takes precedence.
+            None => {
+                let _ = start_logger(LoggerConfig::default());
+            }
+        }
+
+        let mut builder = Self::new_without_logger(
+            manifest.asset_dir,
+            initial_state,
+            manifest.threading,
+        )?.with_frame_limit_config(manifest.frame_limit);
+
+        if let Some(rate) = manifest.fixed_updates_per_second {
+            builder = builder.with_fixed_step_length(Duration::from_secs(1) / rate.max(1));
+        }
+        if let Some(max_steps) = manifest.max_fixed_steps {
+            builder = builder.with_max_fixed_steps(max_steps);
+        }
+        for (name, dir) in manifest.sources {
+            builder = builder.with_source(name, Directory::new(dir));
+        }
+        let hot_reload = manifest.hot_reload.strategy();
+        let reload_configs = hot_reload.is_some();
+        if let Some(strategy) = hot_reload {
+            builder = builder.with_bundle(HotReloadBundle::new(strategy))?;
+        }
+        builder = builder.with_resource(manifest.display.unwrap_or_default());
+
+        for bundle in manifest.bundles {
+            builder = match bundle {
+                BuiltinBundle::Input => {
+                    let input = InputBundle::<String, String>::new();
+                    match manifest.input_bindings {
+                        Some(ref bindings) => {
+                            let builder =
+                                builder.with_bundle(input.with_bindings_from_file(bindings))?;
+                            if reload_configs {
+                                let reload = ConfigReloadSystem::new(
+                                    bindings,
+                                    |input: &mut InputHandler<String, String>, bindings| {
+                                        input.bindings = bindings
+                                    },
+                                );
+                                builder.with(reload, "input_bindings_reload", &["input_system"])
+                            } else {
+                                builder
+                            }
+                        }
+                        None => builder.with_bundle(input)?,
+                    }
+                }
+                BuiltinBundle::Transform => builder.with_bundle(TransformBundle::new())?,
+                BuiltinBundle::Render => builder.with_bundle(RenderBundle::new())?,
+                BuiltinBundle::Ui => builder.with_bundle(UiBundle::new())?,
+            };
+        }
+
+        Ok(builder)
+    }
+
     /// Registers a component into the entity-component-system. This method
     /// takes no options other than the component type which is defined
     /// using a 'turbofish'. See the example for what this looks like.
@@ -539,6 +1075,57 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
         self
     }
 
+    /// Adds a given system to the fixed update loop.
+    ///
+    /// Fixed systems are dispatched once per fixed time step, right after
+    /// `State::fixed_update`, instead of once per frame. When a frame takes longer than the fixed
+    /// time step, they run several times in that frame to catch up.
+    ///
+    /// __Note:__ all dependencies must be added before you add the system, and dependencies can
+    /// only name other fixed systems.
+    ///
+    /// # Parameters
+    ///
+    /// - `system`: The system that is to be added to the fixed update loop.
+    /// - `name`: A unique string to identify the system by, see [`with`](#method.with).
+    /// - `dependencies`: A list of named fixed systems that _must_ have completed running
+    ///                 before this system is permitted to run.
+    ///
+    /// # Returns
+    ///
+    /// This function returns ApplicationBuilder after it has modified it.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`with`](#method.with).
+    ///
+    /// # Examples
+    ///
+    /// ~~~no_run
+    /// use amethyst::prelude::*;
+    /// use amethyst::ecs::System;
+    ///
+    /// struct NullState;
+    /// impl State for NullState {}
+    ///
+    /// struct PhysicsSystem;
+    /// impl<'a> System<'a> for PhysicsSystem {
+    ///     type SystemData = ();
+    ///     fn run(&mut self, _: Self::SystemData) {}
+    /// }
+    ///
+    /// Application::build("assets/", NullState)
+    ///     .expect("Failed to initialize")
+    ///     .with_fixed(PhysicsSystem, "physics", &[]);
+    /// ~~~
+    pub fn with_fixed<S>(mut self, system: S, name: &str, dependencies: &[&str]) -> Self
+    where
+        for<'c> S: System<'c> + Send + 'a,
+    {
+        self.fixed_disp_builder = self.fixed_disp_builder.add(system, name, dependencies);
+        self
+    }
+
     /// Add a given thread-local system to the game loop.
     ///
     /// A thread-local system is one that _must_ run on the main thread of the
@@ -589,6 +1176,80 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
         self
     }
 
+    /// Forwards the events of an event channel to the states.
+    ///
+    /// Every frame, the events written to the `EventChannel<C>` resource are converted with `f`
+    /// and passed to `State::handle_custom_event` of the active state. Events for which `f`
+    /// returns `None` are skipped. This allows states to react to input actions, UI events or
+    /// gameplay events without polling the channels themselves.
+    ///
+    /// # Parameters
+    ///
+    /// - `f`: Converts an event of the channel into the custom event type of the states.
+    ///
+    /// # Type Parameters
+    ///
+    /// - `C`: The event type of the channel.
+    /// - `F`: The conversion function.
+    ///
+    /// # Returns
+    ///
+    /// This function returns ApplicationBuilder after it has modified it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `EventChannel<C>` resource was not added yet, so make sure to call this
+    /// after the bundle which adds the channel.
+    ///
+    /// # Examples
+    ///
+    /// ~~~no_run
+    /// use amethyst::prelude::*;
+    /// use amethyst::input::{InputBundle, InputEvent};
+    ///
+    /// enum GameEvent {
+    ///     Action(String),
+    /// }
+    ///
+    /// struct Game;
+    /// impl State<GameEvent> for Game {
+    ///     fn handle_custom_event(&mut self, _: &mut World, event: GameEvent) -> Trans<GameEvent> {
+    ///         match event {
+    ///             GameEvent::Action(ref action) if action == "quit" => Trans::Quit,
+    ///             _ => Trans::None,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut game = ApplicationBuilder::<_, GameEvent>::new("assets/", Game)
+    ///     .expect("Failed to initialize")
+    ///     .with_bundle(InputBundle::<String, String>::new())
+    ///     .expect("Failed to add bundle")
+    ///     .with_event_channel(|event: &InputEvent<String>| match *event {
+    ///         InputEvent::ActionPressed(ref action) => Some(GameEvent::Action(action.clone())),
+    ///         _ => None,
+    ///     })
+    ///     .build()
+    ///     .expect("Failed to create Application");
+    ///
+    /// game.run();
+    /// ~~~
+    pub fn with_event_channel<C, F>(mut self, f: F) -> Self
+    where
+        C: Send + Sync + 'static,
+        F: FnMut(&C) -> Option<E> + 'b,
+    {
+        let reader = self.world
+            .read_resource::<EventChannel<C>>()
+            .register_reader();
+        self.event_forwarders.push(Box::new(ChannelForwarder {
+            reader,
+            f,
+            marker: PhantomData,
+        }));
+        self
+    }
+
     /// Add a local `RunNow` system.
     ///
     /// The added system will be dispatched after all normal
@@ -627,7 +1288,9 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
     where
         for<'c> S: RunNow<'c> + 'b,
     {
-        self.locals.push(Box::new(system));
+        let name = format!("local_{}", self.locals.len());
+        self.locals
+            .push(Box::new(ProfiledLocal(Profiled::new(system, name))));
         self
     }
 
@@ -647,6 +1310,9 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
     ///
     /// # Errors
     ///
+    /// Returns an error if the resources or systems the bundle requires haven't been
+    /// added yet, see `ECSBundle::required_resources` and `ECSBundle::required_systems`.
+    ///
     /// This function creates systems and resources, which use any number of dependent
     /// crates or APIs, which could result in any number of errors.
     /// See each individual bundle for a description of the errors it could produce.
@@ -655,9 +1321,50 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
     where
         B: ECSBundle<'a, 'b>,
     {
+        check_requirements(&bundle, &self.world, self.disp_builder.systems())
+            .map_err(Error::Core)?;
+        let name = bundle.name().to_owned();
+        let provided = bundle.provided_systems();
+        let added = self.disp_builder.systems().len();
         self.disp_builder = bundle
-            .build(&mut self.world, self.disp_builder)
+            .build_systems(&mut self.world, self.disp_builder)
             .map_err(|err| Error::Core(err))?;
+        check_provided_systems(&name, &provided, &self.disp_builder.systems()[added..]);
+        Ok(self)
+    }
+
+    /// Add a given ECS bundle to the fixed update loop.
+    ///
+    /// This works like [`with_bundle`](#method.with_bundle), except that the systems of the
+    /// bundle are added to the fixed update loop, see [`with_fixed`](#method.with_fixed).
+    ///
+    /// # Parameters
+    ///
+    /// - `bundle`: The bundle to add
+    ///
+    /// # Returns
+    ///
+    /// This function returns ApplicationBuilder after it has modified it, this is
+    /// wrapped in a `Result`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bundle's requirements aren't met, the required systems have
+    /// to be in the fixed update loop as well.
+    /// See each individual bundle for a description of the errors it could produce.
+    pub fn with_fixed_bundle<B>(mut self, bundle: B) -> Result<Self>
+    where
+        B: ECSBundle<'a, 'b>,
+    {
+        check_requirements(&bundle, &self.world, self.fixed_disp_builder.systems())
+            .map_err(Error::Core)?;
+        let name = bundle.name().to_owned();
+        let provided = bundle.provided_systems();
+        let added = self.fixed_disp_builder.systems().len();
+        self.fixed_disp_builder = bundle
+            .build_systems(&mut self.world, self.fixed_disp_builder)
+            .map_err(|err| Error::Core(err))?;
+        check_provided_systems(&name, &provided, &self.fixed_disp_builder.systems()[added..]);
         Ok(self)
     }
 
@@ -766,6 +1473,44 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
         self
     }
 
+    /// Sets the maximum number of fixed updates run in a single frame, defaults to 5.
+    ///
+    /// If a frame takes longer than this many fixed steps, the remaining time is dropped to
+    /// keep the game from falling further and further behind.
+    ///
+    /// # Parameters
+    ///
+    /// `max_steps`: The maximum number of fixed updates per frame.
+    ///
+    /// # Returns
+    ///
+    /// This function returns the ApplicationBuilder after modifying it.
+    pub fn with_max_fixed_steps(self, max_steps: u32) -> Self {
+        self.world
+            .write_resource::<Time>()
+            .set_max_fixed_steps(max_steps);
+        self
+    }
+
+    /// Advances `Time` by a fixed duration every frame, instead of the measured wall-clock
+    /// time.
+    ///
+    /// This makes frame timing deterministic, which is useful for tests. Note that the
+    /// frame limiter still applies, use `FrameRateLimitStrategy::Unlimited` to run frames
+    /// as fast as possible.
+    ///
+    /// # Parameters
+    ///
+    /// `delta`: The delta time of every frame.
+    ///
+    /// # Returns
+    ///
+    /// This function returns the ApplicationBuilder after modifying it.
+    pub fn with_fixed_delta_time(mut self, delta: Duration) -> Self {
+        self.fixed_delta_time = Some(delta);
+        self
+    }
+
     /// Tells the resulting application window to ignore close events if ignore is true.
     /// This will make your game window unresponsive to operating system close commands.
     /// Use with caution.
@@ -782,6 +1527,132 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
         self
     }
 
+    /// Makes the resulting application run without a window.
+    ///
+    /// A headless application does not read window events, so states never receive them
+    /// through `State::handle_event` and window close events have no effect. The state machine,
+    /// dispatcher, `Time`, `FrameLimiter` and the asset `Loader` work as usual, and the
+    /// `EventChannel<Event>` resource stays in place so bundles like `InputBundle` can be used
+    /// unchanged.
+    ///
+    /// Do not add a `RenderSystem` to a headless application, because building it opens a
+    /// window.
+    ///
+    /// # Returns
+    ///
+    /// This function returns the ApplicationBuilder after modifying it.
+    ///
+    /// # Examples
+    ///
+    /// ~~~no_run
+    /// use amethyst::prelude::*;
+    /// use amethyst::core::transform::TransformBundle;
+    ///
+    /// struct NullState;
+    /// impl State for NullState {}
+    ///
+    /// let mut game = Application::build("assets/", NullState)
+    ///     .expect("Failed to initialize")
+    ///     .headless()
+    ///     .with_bundle(TransformBundle::new())
+    ///     .expect("Failed to add bundle")
+    ///     .build()
+    ///     .expect("Failed to create Application");
+    ///
+    /// game.run();
+    /// ~~~
+    pub fn headless(mut self) -> Self {
+        self.headless = true;
+        self
+    }
+
+    /// Records the session, so it can be replayed with
+    /// [`with_replay`](struct.ApplicationBuilder.html#method.with_replay).
+    ///
+    /// For every frame, the real delta time and all window and device events are
+    /// recorded. The recording is saved as a RON file to `path` once the
+    /// application stops running, or when it is dropped, for example by a panic.
+    ///
+    /// # Parameters
+    ///
+    /// `path`: The file to save the recording to, it must have the `ron` extension.
+    ///
+    /// # Returns
+    ///
+    /// This function returns the ApplicationBuilder after modifying it.
+    pub fn with_recording<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.session = Session::Record(RecordingFile::new(path.as_ref().to_owned()));
+        self
+    }
+
+    /// Replays a session recorded with
+    /// [`with_recording`](struct.ApplicationBuilder.html#method.with_recording).
+    ///
+    /// Instead of measuring the wall-clock time, `Time` is advanced by the recorded delta
+    /// times. The recorded events are passed to `State::handle_event` in place of the live
+    /// window events, and the `InputSystem` reads them instead of the window. Closing the
+    /// window still stops the application. The application stops once all frames are
+    /// replayed.
+    ///
+    /// The replayed events carry the window and device ids of the latest live events. Window
+    /// and device events replayed before the window sent its first event, and all of them in
+    /// a headless application, are only written to the `EventChannel<RecordedEvent>`, they can
+    /// be passed to the states with
+    /// [`with_event_channel`](struct.ApplicationBuilder.html#method.with_event_channel).
+    ///
+    /// # Parameters
+    ///
+    /// `path`: The RON file containing the recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the recording can't be loaded.
+    ///
+    /// # Examples
+    ///
+    /// ~~~no_run
+    /// use amethyst::prelude::*;
+    ///
+    /// struct NullState;
+    /// impl State for NullState {}
+    ///
+    /// let mut game = Application::build("assets/", NullState)
+    ///     .expect("Failed to initialize")
+    ///     .with_replay("bug_report.ron")
+    ///     .expect("Failed to load recording")
+    ///     .build()
+    ///     .expect("Failed to create Application");
+    ///
+    /// game.run();
+    /// ~~~
+    pub fn with_replay<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        let recording = Recording::load_no_fallback(path).map_err(Error::Config)?;
+        self.session = Session::Replay { recording, next: 0 };
+        Ok(self)
+    }
+
+    /// Records the state of the `InputHandler<AX, AC>` resource at the end of every frame.
+    ///
+    /// While recording, the state is saved along with the frame. While replaying, it is
+    /// compared to the recorded state, and a `Severity::Warning` `ErrorEvent` is reported
+    /// if the replayed session diverged from the recording.
+    ///
+    /// # Type Parameters
+    ///
+    /// - `AX`, `AC`: The axis and action types of the `InputHandler`.
+    ///
+    /// # Returns
+    ///
+    /// This function returns the ApplicationBuilder after modifying it.
+    pub fn with_input_state_check<AX, AC>(mut self) -> Self
+    where
+        AX: Hash + Eq + Clone + Send + Sync + 'static,
+        AC: Hash + Eq + Clone + Send + Sync + 'static,
+    {
+        self.input_state = Some(input_state::<AX, AC>);
+        self
+    }
+
     /// Register a new asset type with the Application. All required components
     /// related to the storage of this asset type will be registered. Since
     /// Amethyst uses AssetFutures to allow for async content loading, Amethyst
@@ -839,9 +1710,9 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
     ///
     /// See the [example show for `ApplicationBuilder::new()`](struct.ApplicationBuilder.html#examples)
     /// for an example on how this method is used.
-    pub fn build(self) -> Result<Application<'a, 'b>>
+    pub fn build(self) -> Result<Application<'a, 'b, E>>
     where
-        T: State + 'a,
+        T: State<E> + 'a,
     {
         #[cfg(feature = "profiler")]
         register_thread_with_profiler("Main".into());
@@ -849,18 +1720,252 @@ impl<'a, 'b, T> ApplicationBuilder<'a, 'b, T> {
         profile_scope!("new");
 
         let pool = self.world.read_resource::<Arc<ThreadPool>>().clone();
-        let reader_id = self.world
-            .write_resource::<EventChannel<Event>>()
+        let reader_id = if self.headless {
+            None
+        } else {
+            Some(
+                self.world
+                    .write_resource::<EventChannel<Event>>()
+                    .register_reader(),
+            )
+        };
+        let errors_reader_id = self.world
+            .write_resource::<EventChannel<ErrorEvent>>()
             .register_reader();
+        let mut world = self.world;
+        if let Session::Replay { .. } = self.session {
+            world.add_resource(InputSource::Recording);
+        }
 
         Ok(Application {
-            world: self.world,
+            world,
             // config: self.config,
             states: StateMachine::new(self.initial_state),
+            event_forwarders: self.event_forwarders,
             events_reader_id: reader_id,
-            dispatcher: self.disp_builder.with_pool(pool).build(),
+            errors_reader_id,
+            dispatcher: self.disp_builder.into_inner().with_pool(pool.clone()).build(),
+            fixed_dispatcher: self.fixed_disp_builder.into_inner().with_pool(pool).build(),
             locals: self.locals,
             ignore_window_close: self.ignore_window_close,
+            session: self.session,
+            rebuilder: EventRebuilder::new(),
+            input_state: self.input_state,
+            fixed_delta_time: self.fixed_delta_time,
+            initialized: false,
         })
     }
 }
+
+/// Reads events from a channel in the `World` and converts them into custom state events.
+trait EventForwarder<E> {
+    fn forward(&mut self, world: &World, events: &mut Vec<E>);
+}
+
+struct ChannelForwarder<C, F> {
+    reader: ReaderId,
+    f: F,
+    marker: PhantomData<C>,
+}
+
+impl<C, F, E> EventForwarder<E> for ChannelForwarder<C, F>
+where
+    C: Send + Sync + 'static,
+    F: FnMut(&C) -> Option<E>,
+{
+    fn forward(&mut self, world: &World, events: &mut Vec<E>) {
+        let f = &mut self.f;
+        if let Ok(data) = world
+            .read_resource::<EventChannel<C>>()
+            .lossy_read(&mut self.reader)
+        {
+            events.extend(data.filter_map(|event| f(event)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use state::Trans;
+
+    struct QuitAfter(u64);
+
+    impl State for QuitAfter {
+        fn update(&mut self, world: &mut World) -> Trans {
+            if world.read_resource::<Time>().frame_number() + 1 >= self.0 {
+                Trans::Quit
+            } else {
+                Trans::None
+            }
+        }
+    }
+
+    #[test]
+    fn headless_run() {
+        let mut app = Application::build("./", QuitAfter(5))
+            .unwrap()
+            .headless()
+            .build()
+            .unwrap();
+        assert!(app.is_headless());
+
+        app.run();
+
+        assert_eq!(app.world.read_resource::<Time>().frame_number(), 5);
+    }
+
+    #[test]
+    fn run_frames() {
+        let mut app = Application::build("./", QuitAfter(5))
+            .unwrap()
+            .headless()
+            .with_fixed_delta_time(Duration::from_millis(10))
+            .build()
+            .unwrap();
+
+        assert!(app.run_frames(3));
+        {
+            let time = app.world.read_resource::<Time>();
+            assert_eq!(time.frame_number(), 3);
+            assert_eq!(time.absolute_real_time(), Duration::from_millis(30));
+        }
+
+        assert!(!app.run_frames(10));
+        assert_eq!(app.world.read_resource::<Time>().frame_number(), 5);
+        assert!(!app.step());
+    }
+
+    #[test]
+    fn frame_profile() {
+        struct Noop;
+
+        impl<'a> System<'a> for Noop {
+            type SystemData = ();
+
+            fn run(&mut self, _: ()) {}
+        }
+
+        let mut app = Application::build("./", QuitAfter(100))
+            .unwrap()
+            .headless()
+            .with(Noop, "noop", &[])
+            .build()
+            .unwrap();
+        app.run_frames(2);
+
+        let profile = app.world.read_resource::<FrameProfile>();
+        assert_eq!(profile.history().len(), 2);
+        let frame = profile.last_frame().unwrap();
+        assert_eq!(frame.frame_number, 1);
+        assert!(
+            frame
+                .spans
+                .iter()
+                .any(|span| span.name == "noop" && span.kind == SpanKind::System)
+        );
+        assert!(
+            frame
+                .spans
+                .iter()
+                .any(|span| span.name == "dispatch" && span.kind == SpanKind::Phase)
+        );
+    }
+
+    #[test]
+    fn replay_recorded_time() {
+        use std::env::temp_dir;
+
+        let deltas = [0, 10, 20];
+        let recording = Recording {
+            frames: deltas
+                .iter()
+                .map(|&ms| RecordedFrame {
+                    delta: Duration::from_millis(ms),
+                    ..RecordedFrame::default()
+                })
+                .collect(),
+        };
+        let path = temp_dir().join("amethyst_replay_recorded_time.ron");
+        recording.write(&path).unwrap();
+
+        let mut app = Application::build("./", QuitAfter(100))
+            .unwrap()
+            .headless()
+            .with_replay(&path)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(app.is_replaying());
+
+        app.run();
+
+        let time = app.world.read_resource::<Time>();
+        assert_eq!(time.frame_number(), 3);
+        assert_eq!(time.absolute_real_time(), Duration::from_millis(30));
+    }
+
+    #[test]
+    fn save_recording_on_drop() {
+        use std::env::temp_dir;
+        use std::process;
+
+        let path = temp_dir().join(format!("amethyst_recording_drop_{}.ron", process::id()));
+        let mut app = Application::build("./", QuitAfter(100))
+            .unwrap()
+            .headless()
+            .with_recording(&path)
+            .build()
+            .unwrap();
+        assert!(app.run_frames(3));
+        drop(app);
+
+        let recording = Recording::load_no_fallback(&path).unwrap();
+        assert_eq!(recording.frames.len(), 3);
+    }
+
+    struct QuitOnFocusLost;
+
+    impl State<RecordedEvent> for QuitOnFocusLost {
+        fn handle_event(&mut self, _: &mut World, _: Event) -> Trans<RecordedEvent> {
+            panic!("Window events can't be rebuilt without a window");
+        }
+
+        fn handle_custom_event(
+            &mut self,
+            _: &mut World,
+            event: RecordedEvent,
+        ) -> Trans<RecordedEvent> {
+            match event {
+                RecordedEvent::Focused(false) => Trans::Quit,
+                _ => Trans::None,
+            }
+        }
+    }
+
+    #[test]
+    fn replay_events_to_states() {
+        use std::env::temp_dir;
+        use std::process;
+
+        let mut recording = Recording {
+            frames: vec![RecordedFrame::default(); 5],
+        };
+        recording.frames[1].events.push(RecordedEvent::Focused(false));
+        let path = temp_dir().join(format!("amethyst_replay_events_{}.ron", process::id()));
+        recording.write(&path).unwrap();
+
+        let mut app = ApplicationBuilder::<_, RecordedEvent>::new("./", QuitOnFocusLost)
+            .unwrap()
+            .headless()
+            .with_replay(&path)
+            .unwrap()
+            .with_event_channel(|event: &RecordedEvent| Some(event.clone()))
+            .build()
+            .unwrap();
+
+        app.run();
+
+        assert!(app.world.read_resource::<Time>().frame_number() < 5);
+    }
+}
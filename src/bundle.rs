@@ -3,48 +3,83 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use assets::Loader;
-use core::{ECSBundle, Result, Stopwatch, Time};
+use core::{ECSBundle, ErrorEvent, ErrorQueue, FrameProfile, Result, Stopwatch, SystemsBuilder,
+           ThreadPoolConfig, ThreadingConfig, Time};
 use core::frame_limiter::FrameLimiter;
+use config_reload::ConfigReloaded;
+use input::RecordedEvent;
 use ecs::{DispatcherBuilder, World};
 use ecs::common::Errors;
-use rayon::{Configuration, ThreadPool};
-use renderer::Event;
+use rayon::ThreadPool;
+use winit::Event;
 use shrev::EventChannel;
 #[cfg(feature = "profiler")]
 use thread_profiler::register_thread_with_profiler;
 
 pub struct AppBundle {
     path: PathBuf,
+    threading: ThreadingConfig,
 }
 
 impl AppBundle {
     pub fn new<P: AsRef<Path>>(path: P) -> Self {
         Self {
             path: path.as_ref().to_owned(),
+            threading: ThreadingConfig::default(),
         }
     }
+
+    pub fn with_threading(mut self, threading: ThreadingConfig) -> Self {
+        self.threading = threading;
+        self
+    }
+}
+
+fn create_pool(
+    config: &ThreadPoolConfig,
+    _profiler_name: &'static str,
+) -> Result<Arc<ThreadPool>> {
+    let cfg = config.configuration();
+    #[cfg(feature = "profiler")]
+    let cfg = cfg.start_handler(move |index| {
+        register_thread_with_profiler(format!("{}{}", _profiler_name, index));
+    });
+    let pool = ThreadPool::new(cfg).map_err(|err| err.description().to_string())?;
+
+    Ok(Arc::new(pool))
 }
 
 impl<'a, 'b> ECSBundle<'a, 'b> for AppBundle {
     fn build(
         self,
         world: &mut World,
-        dispatcher: DispatcherBuilder<'a, 'b>,
+        builder: DispatcherBuilder<'a, 'b>,
     ) -> Result<DispatcherBuilder<'a, 'b>> {
-        let cfg = Configuration::new();
-        #[cfg(feature = "profiler")]
-        let cfg = cfg.start_handler(|index| {
-            register_thread_with_profiler(format!("thread_pool{}", index));
-        });
-        let pool = ThreadPool::new(cfg)
-            .map(|p| Arc::new(p))
-            .map_err(|err| err.description().to_string())?;
-        world.add_resource(Loader::new(self.path, pool.clone()));
+        self.build_systems(world, SystemsBuilder::new(builder))
+            .map(SystemsBuilder::into_inner)
+    }
+
+    fn build_systems(
+        self,
+        world: &mut World,
+        dispatcher: SystemsBuilder<'a, 'b>,
+    ) -> Result<SystemsBuilder<'a, 'b>> {
+        let pool = create_pool(&self.threading.workers, "thread_pool")?;
+        let io_pool = match self.threading.io {
+            Some(ref io) => create_pool(io, "io_pool")?,
+            None => pool.clone(),
+        };
+        world.add_resource(Loader::new(self.path, io_pool));
         world.add_resource(EventChannel::<Event>::with_capacity(2000));
+        world.add_resource(EventChannel::<RecordedEvent>::with_capacity(2000));
         world.add_resource(Errors::new());
+        world.add_resource(EventChannel::<ErrorEvent>::with_capacity(200));
+        world.add_resource(ErrorQueue::new());
+        world.add_resource(EventChannel::<ConfigReloaded>::with_capacity(20));
         world.add_resource(pool);
         world.add_resource(FrameLimiter::default());
         world.add_resource(Stopwatch::default());
+        world.add_resource(FrameProfile::default());
         let mut time = Time::default();
         time.set_fixed_time(Duration::new(0, 16666666));
         world.add_resource(time);
@@ -0,0 +1,200 @@
+//! Reloading config files while the game runs.
+
+use std::fs;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use assets::HotReloadStrategy;
+use config::Config;
+use core::{ErrorEvent, ErrorQueue, Severity, Time};
+use ecs::{Fetch, FetchMut, System};
+use renderer::{DisplayConfig, WindowMessages};
+use shred::Resource;
+use shrev::EventChannel;
+
+/// Written to the `EventChannel<ConfigReloaded>` resource when a config file was reloaded.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ConfigReloaded {
+    /// The path of the reloaded file.
+    pub path: PathBuf,
+}
+
+/// Watches a config file of type `C`, and applies it to the resource `R` when it changes.
+///
+/// The file is checked for changes whenever the `HotReloadStrategy` resource reloads assets,
+/// so it is never reloaded without one. Failing reloads are reported as `ErrorEvent` warnings,
+/// and keep the resource as it is.
+///
+/// # Examples
+///
+/// ~~~no_run
+/// use amethyst::ConfigReloadSystem;
+/// use amethyst::input::InputHandler;
+///
+/// let bindings_reload = ConfigReloadSystem::new(
+///     "resources/input.ron",
+///     |input: &mut InputHandler<String, String>, bindings| input.bindings = bindings,
+/// );
+/// ~~~
+pub struct ConfigReloadSystem<C, R> {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    apply: Box<Fn(&mut R, C) + Send + Sync>,
+    marker: PhantomData<fn() -> C>,
+}
+
+impl<C, R> ConfigReloadSystem<C, R>
+where
+    C: Config,
+    R: Resource,
+{
+    /// Creates a new system watching the file at `path`, which calls `apply` with the
+    /// resource and the new config after each change.
+    pub fn new<P, F>(path: P, apply: F) -> Self
+    where
+        P: AsRef<Path>,
+        F: Fn(&mut R, C) + Send + Sync + 'static,
+    {
+        let path = path.as_ref().to_owned();
+        let modified = modified(&path);
+
+        ConfigReloadSystem {
+            path,
+            modified,
+            apply: Box::new(apply),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<C> ConfigReloadSystem<C, C>
+where
+    C: Config + Resource,
+{
+    /// Creates a new system watching the file at `path`, which replaces the `C` resource
+    /// after each change.
+    pub fn replace<P: AsRef<Path>>(path: P) -> Self {
+        Self::new(path, |resource: &mut C, config| *resource = config)
+    }
+}
+
+impl ConfigReloadSystem<DisplayConfig, WindowMessages> {
+    /// Creates a new system watching the `DisplayConfig` at `path`, which updates the title
+    /// and dimensions of the window after each change.
+    pub fn window<P: AsRef<Path>>(path: P) -> Self {
+        Self::new(path, |messages: &mut WindowMessages, config: DisplayConfig| {
+            messages.send_command(move |window| {
+                window.set_title(&config.title);
+                if let Some((width, height)) = config.dimensions {
+                    window.set_inner_size(width, height);
+                }
+            });
+        })
+    }
+}
+
+impl<'a, C, R> System<'a> for ConfigReloadSystem<C, R>
+where
+    C: Config,
+    R: Resource,
+{
+    type SystemData = (
+        Fetch<'a, Time>,
+        Option<Fetch<'a, HotReloadStrategy>>,
+        FetchMut<'a, R>,
+        FetchMut<'a, EventChannel<ConfigReloaded>>,
+        Option<Fetch<'a, ErrorQueue>>,
+    );
+
+    fn run(&mut self, (time, strategy, mut resource, mut reloaded, errors): Self::SystemData) {
+        match strategy {
+            Some(ref strategy) if strategy.needs_reload(time.frame_number()) => {}
+            _ => return,
+        }
+
+        let modified = modified(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+
+        match C::load_no_fallback(&self.path) {
+            Ok(config) => {
+                (self.apply)(&mut resource, config);
+                reloaded.single_write(ConfigReloaded {
+                    path: self.path.clone(),
+                });
+            }
+            Err(err) => if let Some(errors) = errors {
+                errors.push(ErrorEvent::new(
+                    Severity::Warning,
+                    format!(
+                        "Failed to reload config {}: {}",
+                        self.path.display(),
+                        err
+                    ),
+                ));
+            },
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::File;
+    use std::io::Write;
+    use std::process;
+
+    use assets::HotReloadStrategy;
+    use ecs::World;
+    use shred::RunNow;
+
+    use super::*;
+
+    #[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+    struct Speed(u32);
+
+    #[test]
+    fn reloads_changed_files() {
+        let path = env::temp_dir().join(format!("amethyst_config_reload_{}.ron", process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut world = World::new();
+        world.add_resource(Time::default());
+        world.add_resource(Speed(1));
+        world.add_resource(EventChannel::<ConfigReloaded>::new());
+        let mut reader = world
+            .write_resource::<EventChannel<ConfigReloaded>>()
+            .register_reader();
+
+        // The file is created after the system, so it counts as changed even on file systems
+        // with coarse modification times.
+        let mut system = ConfigReloadSystem::<Speed, Speed>::replace(&path);
+        File::create(&path).unwrap().write_all(b"(2)").unwrap();
+
+        system.run_now(&world.res);
+        assert_eq!(*world.read_resource::<Speed>(), Speed(1));
+
+        let mut strategy = HotReloadStrategy::when_triggered();
+        strategy.trigger();
+        world.add_resource(strategy);
+        assets::HotReloadSystem.run_now(&world.res);
+        world.write_resource::<Time>().increment_frame_number();
+        system.run_now(&world.res);
+
+        assert_eq!(*world.read_resource::<Speed>(), Speed(2));
+        let reloaded = world.read_resource::<EventChannel<ConfigReloaded>>();
+        let events = reloaded.lossy_read(&mut reader).unwrap().collect::<Vec<_>>();
+        assert_eq!(events, vec![&ConfigReloaded { path: path.clone() }]);
+
+        let _ = fs::remove_file(&path);
+    }
+}
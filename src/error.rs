@@ -23,6 +23,8 @@ pub enum Error {
     Config(ConfigError),
     /// Core error.
     Core(core::Error),
+    /// Logger error.
+    Logger(String),
 }
 
 impl StdError for Error {
@@ -31,6 +33,7 @@ impl StdError for Error {
             Error::Application => "Application error!",
             Error::Config(_) => "Configuration error!",
             Error::Core(_) => "Core error!",
+            Error::Logger(_) => "Logger error!",
         }
     }
 
@@ -48,6 +51,7 @@ impl Display for Error {
             Error::Application => write!(fmt, "Application initialization failed!"),
             Error::Config(ref e) => write!(fmt, "Configuration loading failed: {}", e),
             Error::Core(ref e) => write!(fmt, "System creation failed: {}", e),
+            Error::Logger(ref e) => write!(fmt, "Logger initialization failed: {}", e),
         }
     }
 }
@@ -71,16 +71,26 @@ pub extern crate winit;
 
 #[macro_use]
 extern crate derivative;
+#[macro_use]
+extern crate log;
 extern crate rayon;
+#[macro_use]
+extern crate serde;
 
 pub use self::app::{Application, ApplicationBuilder};
+pub use self::config_reload::{ConfigReloadSystem, ConfigReloaded};
 pub use self::error::{Error, Result};
+pub use self::logger::{start_logger, LogLevel, LoggerConfig};
+pub use self::manifest::{BuiltinBundle, GameManifest, HotReload};
 pub use self::state::{State, StateMachine, Trans};
 
 pub mod prelude;
 
 mod app;
+mod config_reload;
 mod error;
+mod logger;
+mod manifest;
 mod state;
 mod vergen;
 mod bundle;
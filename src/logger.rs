@@ -0,0 +1,206 @@
+//! Logging of engine and game messages.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use log::{self, LogLevelFilter, LogMetadata, LogRecord};
+
+use error::{Error, Result};
+
+/// The verbosity of a logger.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum LogLevel {
+    /// Disables logging.
+    Off,
+    /// Only errors.
+    Error,
+    /// Warnings and errors.
+    Warn,
+    /// Informational messages, warnings and errors.
+    Info,
+    /// Debug messages and everything above.
+    Debug,
+    /// Everything.
+    Trace,
+}
+
+impl From<LogLevel> for LogLevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Off => LogLevelFilter::Off,
+            LogLevel::Error => LogLevelFilter::Error,
+            LogLevel::Warn => LogLevelFilter::Warn,
+            LogLevel::Info => LogLevelFilter::Info,
+            LogLevel::Debug => LogLevelFilter::Debug,
+            LogLevel::Trace => LogLevelFilter::Trace,
+        }
+    }
+}
+
+/// Logger configuration, which can be loaded from a RON file using `amethyst_config::Config`.
+///
+/// ```ron
+/// (
+///     stdout: true,
+///     log_file: Some("game.log"),
+///     level: Info,
+///     modules: [
+///         ("amethyst_assets", Warn),
+///         ("amethyst_renderer::pipe", Error),
+///     ],
+/// )
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LoggerConfig {
+    /// Whether messages are written to stdout.
+    pub stdout: bool,
+    /// A file messages are appended to.
+    pub log_file: Option<PathBuf>,
+    /// The level of messages from modules which aren't listed in `modules`.
+    pub level: LogLevel,
+    /// Levels of specific crates or modules, given by their path.
+    ///
+    /// If several entries match a module, the longest one is used.
+    pub modules: Vec<(String, LogLevel)>,
+}
+
+impl Default for LoggerConfig {
+    fn default() -> Self {
+        LoggerConfig {
+            stdout: true,
+            log_file: None,
+            level: LogLevel::Info,
+            modules: Vec::new(),
+        }
+    }
+}
+
+/// Starts the global logger used by the engine.
+///
+/// `ApplicationBuilder::new` starts a logger with the default config, unless a logger
+/// was set before. To use a custom config, or a different logger implementation, set
+/// it up before creating the application.
+///
+/// # Errors
+///
+/// Returns an error if the log file can't be opened or a logger was already set.
+///
+/// # Examples
+///
+/// ~~~no_run
+/// use amethyst::{start_logger, LoggerConfig};
+/// use amethyst::config::Config;
+///
+/// start_logger(LoggerConfig::load("resources/logger.ron")).expect("Failed to start logger");
+/// ~~~
+pub fn start_logger(config: LoggerConfig) -> Result<()> {
+    let file = match config.log_file {
+        Some(ref path) => Some(OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| {
+                Error::Logger(format!("Failed to open {}: {}", path.display(), e))
+            })?),
+        None => None,
+    };
+
+    let mut modules = config
+        .modules
+        .into_iter()
+        .map(|(module, level)| (module, level.into()))
+        .collect::<Vec<(String, LogLevelFilter)>>();
+    // Longest paths first, so the most specific one matches.
+    modules.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+
+    let logger = Logger {
+        stdout: config.stdout,
+        file: file.map(Mutex::new),
+        level: config.level.into(),
+        modules,
+    };
+
+    log::set_logger(|max_level| {
+        max_level.set(logger.max_level());
+        Box::new(logger)
+    }).map_err(|e| Error::Logger(e.to_string()))
+}
+
+struct Logger {
+    stdout: bool,
+    file: Option<Mutex<File>>,
+    level: LogLevelFilter,
+    modules: Vec<(String, LogLevelFilter)>,
+}
+
+impl Logger {
+    fn level_of(&self, target: &str) -> LogLevelFilter {
+        self.modules
+            .iter()
+            .find(|&&(ref module, _)| {
+                target.starts_with(&module[..])
+                    && target[module.len()..]
+                        .chars()
+                        .next()
+                        .map(|c| c == ':')
+                        .unwrap_or(true)
+            })
+            .map(|&(_, level)| level)
+            .unwrap_or(self.level)
+    }
+
+    fn max_level(&self) -> LogLevelFilter {
+        self.modules
+            .iter()
+            .map(|&(_, level)| level)
+            .fold(self.level, ::std::cmp::max)
+    }
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &LogMetadata) -> bool {
+        metadata.level() <= self.level_of(metadata.target())
+    }
+
+    fn log(&self, record: &LogRecord) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let message = format!("[{}][{}] {}", record.level(), record.target(), record.args());
+        if self.stdout {
+            println!("{}", message);
+        }
+        if let Some(ref file) = self.file {
+            // There's nowhere to report failing to log.
+            let _ = writeln!(file.lock().unwrap(), "{}", message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_levels() {
+        let logger = Logger {
+            stdout: false,
+            file: None,
+            level: LogLevelFilter::Info,
+            modules: vec![
+                ("amethyst_renderer::pipe".to_owned(), LogLevelFilter::Error),
+                ("amethyst_renderer".to_owned(), LogLevelFilter::Debug),
+            ],
+        };
+
+        assert_eq!(logger.level_of("amethyst_renderer::pipe::effect"), LogLevelFilter::Error);
+        assert_eq!(logger.level_of("amethyst_renderer::system"), LogLevelFilter::Debug);
+        assert_eq!(logger.level_of("amethyst_renderer_extra"), LogLevelFilter::Info);
+        assert_eq!(logger.level_of("amethyst_assets"), LogLevelFilter::Info);
+        assert_eq!(logger.max_level(), LogLevelFilter::Debug);
+    }
+}
@@ -0,0 +1,158 @@
+//! Data-driven application setup.
+
+use std::path::{Path, PathBuf};
+
+use assets::HotReloadStrategy;
+use core::ThreadingConfig;
+use core::frame_limiter::FrameRateLimitConfig;
+use logger::LoggerConfig;
+use renderer::DisplayConfig;
+
+/// Describes how an application is set up, usually loaded from a RON file with
+/// `ApplicationBuilder::from_manifest`.
+///
+/// Every field is optional, missing ones use their default.
+///
+/// ```ron
+/// (
+///     display: Some((
+///         title: "Pong",
+///         dimensions: Some((500, 500)),
+///     )),
+///     frame_limit: (
+///         strategy: Yield,
+///         fps: 144,
+///     ),
+///     fixed_updates_per_second: Some(60),
+///     asset_dir: "assets/",
+///     sources: [
+///         ("mods", "mods/"),
+///     ],
+///     hot_reload: Every(2),
+///     input_bindings: Some("resources/input.ron"),
+///     bundles: [Input, Transform, Render, Ui],
+/// )
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GameManifest {
+    /// The window configuration.
+    ///
+    /// It is added as a resource, so it can be passed to the `RenderSystem`.
+    pub display: Option<DisplayConfig>,
+    /// The frame rate limit.
+    pub frame_limit: FrameRateLimitConfig,
+    /// The number of fixed updates per second, defaults to 60.
+    pub fixed_updates_per_second: Option<u32>,
+    /// The maximum number of fixed updates per frame, defaults to 5.
+    pub max_fixed_steps: Option<u32>,
+    /// The default directory assets are loaded from.
+    pub asset_dir: PathBuf,
+    /// Additional directories assets can be loaded from, by name.
+    pub sources: Vec<(String, PathBuf)>,
+    /// How often changed assets and config files are reloaded.
+    pub hot_reload: HotReload,
+    /// A RON file with the `Bindings<String, String>` of the `InputBundle`.
+    /// It is reloaded by a `ConfigReloadSystem` if `hot_reload` is enabled.
+    pub input_bindings: Option<PathBuf>,
+    /// The built-in bundles to add, in order.
+    pub bundles: Vec<BuiltinBundle>,
+    /// The thread pool configuration.
+    pub threading: ThreadingConfig,
+    /// The logger configuration, the default logger is used if this is `None`.
+    pub logger: Option<LoggerConfig>,
+}
+
+impl Default for GameManifest {
+    fn default() -> Self {
+        GameManifest {
+            display: None,
+            frame_limit: FrameRateLimitConfig::default(),
+            fixed_updates_per_second: None,
+            max_fixed_steps: None,
+            asset_dir: PathBuf::from("assets"),
+            sources: Vec::new(),
+            hot_reload: HotReload::Never,
+            input_bindings: None,
+            bundles: Vec::new(),
+            threading: ThreadingConfig::default(),
+            logger: None,
+        }
+    }
+}
+
+impl GameManifest {
+    /// Makes all relative paths in the manifest relative to `dir` instead of the
+    /// working directory.
+    pub fn resolve_paths<P: AsRef<Path>>(&mut self, dir: P) {
+        let dir = dir.as_ref();
+        self.asset_dir = dir.join(&self.asset_dir);
+        for &mut (_, ref mut path) in &mut self.sources {
+            *path = dir.join(&*path);
+        }
+        if let Some(ref mut path) = self.input_bindings {
+            *path = dir.join(&*path);
+        }
+        if let Some(LoggerConfig {
+            log_file: Some(ref mut path),
+            ..
+        }) = self.logger
+        {
+            *path = dir.join(&*path);
+        }
+    }
+}
+
+/// How often changed assets are reloaded, see `HotReloadStrategy`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum HotReload {
+    /// Hot reloading is disabled.
+    Never,
+    /// Changed assets are reloaded every `n` seconds.
+    Every(u8),
+    /// Changed assets are reloaded after calling `HotReloadStrategy::trigger`.
+    WhenTriggered,
+}
+
+impl HotReload {
+    /// Returns the matching strategy, or `None` if hot reloading is disabled.
+    pub fn strategy(&self) -> Option<HotReloadStrategy> {
+        match *self {
+            HotReload::Never => None,
+            HotReload::Every(n) => Some(HotReloadStrategy::every(n)),
+            HotReload::WhenTriggered => Some(HotReloadStrategy::when_triggered()),
+        }
+    }
+}
+
+/// A bundle provided by the engine which can be enabled in a `GameManifest`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum BuiltinBundle {
+    /// `InputBundle<String, String>`, using the manifest's `input_bindings`.
+    Input,
+    /// `TransformBundle`.
+    Transform,
+    /// `RenderBundle`. The `RenderSystem` still needs to be added with its pipeline.
+    Render,
+    /// `UiBundle`, which requires the `Render` bundle.
+    Ui,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_paths() {
+        let mut manifest = GameManifest {
+            sources: vec![("mods".to_owned(), PathBuf::from("mods"))],
+            input_bindings: Some(PathBuf::from("/abs/input.ron")),
+            ..GameManifest::default()
+        };
+        manifest.resolve_paths("game");
+
+        assert_eq!(manifest.asset_dir, Path::new("game/assets"));
+        assert_eq!(manifest.sources[0].1, Path::new("game/mods"));
+        assert_eq!(manifest.input_bindings, Some(PathBuf::from("/abs/input.ron")));
+    }
+}
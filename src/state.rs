@@ -1,25 +1,32 @@
 //! Utilities for game state management.
 
-use ecs::World;
+use core::{ErrorEvent, Severity, Timers};
+use ecs::{Dispatcher, FetchMut, World};
 use renderer::Event;
+use shred::SystemData;
 
 /// Types of state transitions.
-pub enum Trans {
+///
+/// `E` is the type of the custom events the states handle, see `State::handle_custom_event`.
+pub enum Trans<E = ()> {
     /// Continue as normal.
     None,
     /// Remove the active state and resume the next state on the stack or stop
     /// if there are none.
     Pop,
     /// Pause the active state and push a new state onto the stack.
-    Push(Box<State>),
+    Push(Box<State<E>>),
     /// Remove the current state on the stack and insert a different one.
-    Switch(Box<State>),
+    Switch(Box<State<E>>),
     /// Stop and remove all states and shut down the engine.
     Quit,
 }
 
 /// A trait which defines game states that can be used by the state machine.
-pub trait State {
+///
+/// `E` is the type of the custom events the state handles, it defaults to `()` for games which
+/// only react to window events.
+pub trait State<E = ()> {
     /// Executed when the game state begins.
     fn on_start(&mut self, _eng: &mut World) {}
 
@@ -33,34 +40,77 @@ pub trait State {
     fn on_resume(&mut self, _eng: &mut World) {}
 
     /// Executed on every frame before updating, for use in reacting to events.
-    fn handle_event(&mut self, _eng: &mut World, _event: Event) -> Trans {
+    fn handle_event(&mut self, _eng: &mut World, _event: Event) -> Trans<E> {
         Trans::None
     }
 
+    /// Executed on every frame before updating, for every custom event forwarded to the state
+    /// machine.
+    ///
+    /// Custom events are read from the event channels registered with
+    /// `ApplicationBuilder::with_event_channel`, after the window events were handled.
+    fn handle_custom_event(&mut self, _eng: &mut World, _event: E) -> Trans<E> {
+        Trans::None
+    }
+
+    /// Executed for every error reported during a frame, see `ErrorEvent`.
+    ///
+    /// The state can recover from the error, switch to a different state or quit. By default
+    /// fatal errors quit the application and all other errors are ignored.
+    fn on_error(&mut self, _eng: &mut World, error: ErrorEvent) -> Trans<E> {
+        match error.severity {
+            Severity::Fatal => Trans::Quit,
+            _ => Trans::None,
+        }
+    }
+
     /// Executed repeatedly at stable, predictable intervals (1/60th of a second
     /// by default).
-    fn fixed_update(&mut self, _eng: &mut World) -> Trans {
+    fn fixed_update(&mut self, _eng: &mut World) -> Trans<E> {
         Trans::None
     }
 
     /// Executed on every frame immediately, as fast as the engine will allow.
-    fn update(&mut self, _eng: &mut World) -> Trans {
+    fn update(&mut self, _eng: &mut World) -> Trans<E> {
         Trans::None
     }
+
+    /// Executed repeatedly at stable, predictable intervals for every state on the stack, even
+    /// while it is paused. Runs before `fixed_update` of the active state.
+    fn shadow_fixed_update(&mut self, _eng: &mut World) {}
+
+    /// Executed on every frame for every state on the stack, even while it is paused.
+    /// Runs before `update` of the active state.
+    ///
+    /// This is useful for logic which has to keep running below a pushed state, like background
+    /// music or UI animations below a pause menu.
+    fn shadow_update(&mut self, _eng: &mut World) {}
+
+    /// Returns the dispatcher owned by this state, if there is one.
+    ///
+    /// The systems of this dispatcher are run every frame right after `update`, but only while
+    /// this state is the active one. This allows systems which only make sense in a certain
+    /// state to live with it, instead of in the global dispatcher of the `Application`.
+    fn dispatcher(&mut self) -> Option<&mut Dispatcher<'static, 'static>> {
+        None
+    }
 }
 
 /// A simple stack-based state machine (pushdown automaton).
+///
+/// If the `Timers` resource exists, it is kept in sync with the stack, so timers are
+/// paused and removed together with the state they were added in.
 #[derive(Derivative)]
-#[derivative(Debug)]
-pub struct StateMachine<'a> {
+#[derivative(Debug(bound = ""))]
+pub struct StateMachine<'a, E = ()> {
     running: bool,
     #[derivative(Debug = "ignore")]
-    state_stack: Vec<Box<State + 'a>>,
+    state_stack: Vec<Box<State<E> + 'a>>,
 }
 
-impl<'a> StateMachine<'a> {
+impl<'a, E> StateMachine<'a, E> {
     /// Creates a new state machine with the given initial state.
-    pub fn new<S: State + 'a>(initial_state: S) -> StateMachine<'a> {
+    pub fn new<S: State<E> + 'a>(initial_state: S) -> StateMachine<'a, E> {
         StateMachine {
             running: false,
             state_stack: vec![Box::new(initial_state)],
@@ -78,6 +128,7 @@ impl<'a> StateMachine<'a> {
     /// Panics if no states are present in the stack.
     pub fn start(&mut self, engine: &mut World) {
         if !self.running {
+            update_timers(engine, Timers::push_state);
             let state = self.state_stack.last_mut().unwrap();
             state.on_start(engine);
             self.running = true;
@@ -96,9 +147,39 @@ impl<'a> StateMachine<'a> {
         }
     }
 
+    /// Passes a single custom event to the active state to handle.
+    pub fn handle_custom_event(&mut self, engine: &mut World, event: E) {
+        if self.running {
+            let trans = match self.state_stack.last_mut() {
+                Some(state) => state.handle_custom_event(engine, event),
+                None => Trans::None,
+            };
+
+            self.transition(trans, engine);
+        }
+    }
+
+    /// Passes a single error to the active state to handle.
+    pub fn handle_error(&mut self, engine: &mut World, error: ErrorEvent) {
+        if self.running {
+            let trans = match self.state_stack.last_mut() {
+                Some(state) => state.on_error(engine, error),
+                None => Trans::None,
+            };
+
+            self.transition(trans, engine);
+        }
+    }
+
     /// Updates the currently active state at a steady, fixed interval.
+    ///
+    /// Every state on the stack gets its `shadow_fixed_update` called first.
     pub fn fixed_update(&mut self, engine: &mut World) {
         if self.running {
+            for state in &mut self.state_stack {
+                state.shadow_fixed_update(engine);
+            }
+
             let trans = match self.state_stack.last_mut() {
                 Some(state) => state.fixed_update(engine),
                 None => Trans::None,
@@ -108,11 +189,23 @@ impl<'a> StateMachine<'a> {
         }
     }
 
-    /// Updates the currently active state immediately.
+    /// Updates the currently active state immediately, and runs its dispatcher.
+    ///
+    /// Every state on the stack gets its `shadow_update` called first.
     pub fn update(&mut self, engine: &mut World) {
         if self.running {
+            for state in &mut self.state_stack {
+                state.shadow_update(engine);
+            }
+
             let trans = match self.state_stack.last_mut() {
-                Some(state) => state.update(engine),
+                Some(state) => {
+                    let trans = state.update(engine);
+                    if let Some(dispatcher) = state.dispatcher() {
+                        dispatcher.dispatch(&mut engine.res);
+                    }
+                    trans
+                }
                 None => Trans::None,
             };
 
@@ -122,7 +215,7 @@ impl<'a> StateMachine<'a> {
 
     /// Performs a state transition, if requested by either update() or
     /// fixed_update().
-    fn transition(&mut self, request: Trans, engine: &mut World) {
+    fn transition(&mut self, request: Trans<E>, engine: &mut World) {
         if self.running {
             match request {
                 Trans::None => (),
@@ -135,12 +228,14 @@ impl<'a> StateMachine<'a> {
     }
 
     /// Removes the current state on the stack and inserts a different one.
-    fn switch(&mut self, state: Box<State>, engine: &mut World) {
+    fn switch(&mut self, state: Box<State<E>>, engine: &mut World) {
         if self.running {
             if let Some(mut state) = self.state_stack.pop() {
                 state.on_stop(engine);
+                update_timers(engine, Timers::pop_state);
             }
 
+            update_timers(engine, Timers::push_state);
             self.state_stack.push(state);
             let state = self.state_stack.last_mut().unwrap();
             state.on_start(engine);
@@ -148,12 +243,13 @@ impl<'a> StateMachine<'a> {
     }
 
     /// Pauses the active state and pushes a new state onto the state stack.
-    fn push(&mut self, state: Box<State>, engine: &mut World) {
+    fn push(&mut self, state: Box<State<E>>, engine: &mut World) {
         if self.running {
             if let Some(state) = self.state_stack.last_mut() {
                 state.on_pause(engine);
             }
 
+            update_timers(engine, Timers::push_state);
             self.state_stack.push(state);
             let state = self.state_stack.last_mut().unwrap();
             state.on_start(engine);
@@ -166,6 +262,7 @@ impl<'a> StateMachine<'a> {
         if self.running {
             if let Some(mut state) = self.state_stack.pop() {
                 state.on_stop(engine);
+                update_timers(engine, Timers::pop_state);
             }
 
             if let Some(state) = self.state_stack.last_mut() {
@@ -181,6 +278,7 @@ impl<'a> StateMachine<'a> {
         if self.running {
             while let Some(mut state) = self.state_stack.pop() {
                 state.on_stop(engine);
+                update_timers(engine, Timers::pop_state);
             }
 
             self.running = false;
@@ -188,9 +286,16 @@ impl<'a> StateMachine<'a> {
     }
 }
 
+fn update_timers(engine: &World, update: fn(&mut Timers)) {
+    if let Some(mut timers) = Option::<FetchMut<Timers>>::fetch(&engine.res, 0) {
+        update(&mut timers);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ecs::{FetchMut, System};
 
     struct State1(u8);
     struct State2;
@@ -229,4 +334,108 @@ mod tests {
         sm.update(&mut world);
         assert!(!sm.is_running());
     }
+
+    enum Command {
+        Next,
+        Stop,
+    }
+
+    struct Menu;
+    struct Game;
+
+    impl State<Command> for Menu {
+        fn handle_custom_event(&mut self, _: &mut World, event: Command) -> Trans<Command> {
+            match event {
+                Command::Next => Trans::Push(Box::new(Game)),
+                Command::Stop => Trans::Quit,
+            }
+        }
+    }
+
+    impl State<Command> for Game {
+        fn handle_custom_event(&mut self, _: &mut World, event: Command) -> Trans<Command> {
+            match event {
+                Command::Next => Trans::None,
+                Command::Stop => Trans::Pop,
+            }
+        }
+    }
+
+    #[test]
+    fn custom_events() {
+        let mut world = World::new();
+
+        let mut sm = StateMachine::new(Menu);
+        sm.start(&mut world);
+
+        sm.handle_custom_event(&mut world, Command::Next);
+        sm.handle_custom_event(&mut world, Command::Next);
+        assert_eq!(sm.state_stack.len(), 2);
+
+        sm.handle_custom_event(&mut world, Command::Stop);
+        assert_eq!(sm.state_stack.len(), 1);
+        assert!(sm.is_running());
+
+        sm.handle_custom_event(&mut world, Command::Stop);
+        assert!(!sm.is_running());
+    }
+
+    struct Counter(u32);
+    struct Shadowed(u32);
+
+    struct CountSystem;
+
+    impl<'a> System<'a> for CountSystem {
+        type SystemData = FetchMut<'a, Counter>;
+
+        fn run(&mut self, mut counter: Self::SystemData) {
+            counter.0 += 1;
+        }
+    }
+
+    struct Background {
+        dispatcher: Dispatcher<'static, 'static>,
+    }
+
+    struct Overlay;
+
+    impl State for Background {
+        fn update(&mut self, _: &mut World) -> Trans {
+            Trans::Push(Box::new(Overlay))
+        }
+
+        fn shadow_update(&mut self, world: &mut World) {
+            world.write_resource::<Shadowed>().0 += 1;
+        }
+
+        fn dispatcher(&mut self) -> Option<&mut Dispatcher<'static, 'static>> {
+            Some(&mut self.dispatcher)
+        }
+    }
+
+    impl State for Overlay {}
+
+    #[test]
+    fn shadow_update_and_dispatcher() {
+        use ecs::DispatcherBuilder;
+
+        let mut world = World::new();
+        world.add_resource(Counter(0));
+        world.add_resource(Shadowed(0));
+
+        let dispatcher = DispatcherBuilder::new()
+            .add(CountSystem, "count", &[])
+            .build();
+        let mut sm = StateMachine::new(Background { dispatcher });
+        sm.start(&mut world);
+
+        sm.update(&mut world);
+        assert_eq!(world.read_resource::<Counter>().0, 1);
+        assert_eq!(world.read_resource::<Shadowed>().0, 1);
+
+        // `Background` is paused now, so only its shadow update runs.
+        sm.update(&mut world);
+        assert_eq!(world.read_resource::<Counter>().0, 1);
+        assert_eq!(world.read_resource::<Shadowed>().0, 2);
+    }
 }